@@ -3,14 +3,15 @@
 use proc_macro::{TokenStream, Span};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
+use syn::ext::IdentExt;
 use syn::token::{Paren, Brace, Colon, Bracket, Semi, Eq};
 use quote::{quote, ToTokens};
 use syn::{
-    parse_macro_input, DeriveInput, Data, Fields, Ident, Meta, Expr, Lit, ExprLit,
+    parse_macro_input, parse_quote, DeriveInput, Data, Fields, Ident, Meta, Expr, Lit, ExprLit,
     ExprAssign, ExprPath, Generics, Variant, Result, Error, PathSegment,
     FieldsUnnamed, Visibility, Pat, PatStruct, Path, PatTupleStruct, PatIdent,
     FieldPat, Member, Field, Type, TypeArray, LitInt, FieldMutability, PatSlice,
-    ItemImpl, ImplItemType, TypeNever, TypePath, ImplItem
+    ItemImpl, ImplItemType, TypeNever, TypePath, ImplItem, Attribute
 };
 
 /// This macro provides default implementations for all required types in `TypeProvider`.
@@ -89,6 +90,12 @@ pub fn type_provider(args: TokenStream, input: TokenStream) -> TokenStream {
         ..default.clone()
     }));
 
+    types.push(ImplItem::Type(ImplItemType {
+        ident: Ident::new("DiagnosticData", Span::call_site().into()),
+        ty: unit.clone(),
+        ..default.clone()
+    }));
+
     types.push(ImplItem::Type(ImplItemType {
         ident: Ident::new("Configuration", Span::call_site().into()),
         ty: unit.clone(),
@@ -113,6 +120,38 @@ pub fn type_provider(args: TokenStream, input: TokenStream) -> TokenStream {
         ..default.clone()
     }));
 
+    types.push(ImplItem::Type(ImplItemType {
+        ident: Ident::new("ShowDocumentData", Span::call_site().into()),
+        ty: unit.clone(),
+        ..default.clone()
+    }));
+
+    types.push(ImplItem::Type(ImplItemType {
+        ident: Ident::new("TelemetryEvent", Span::call_site().into()),
+        ty: Type::Path(TypePath {
+            qself: None,
+            path: {
+                let mut unit = Path {
+                    leading_colon: Some(Default::default()),
+                    segments: Punctuated::new()
+                };
+
+                unit.segments.push(PathSegment::from(Ident::new("sync_lsp", Span::call_site().into())));
+                unit.segments.push(PathSegment::from(Ident::new("window", Span::call_site().into())));
+                unit.segments.push(PathSegment::from(Ident::new("telemetry", Span::call_site().into())));
+                unit.segments.push(PathSegment::from(Ident::new("UnitTelemetryEvent", Span::call_site().into())));
+                unit
+            }
+        }),
+        ..default.clone()
+    }));
+
+    types.push(ImplItem::Type(ImplItemType {
+        ident: Ident::new("UserEvent", Span::call_site().into()),
+        ty: unit.clone(),
+        ..default.clone()
+    }));
+
     for item in input.items.iter() {
         let ImplItem::Type(item) = item else { continue };
         types.retain(|r#type| {
@@ -137,6 +176,7 @@ pub fn type_provider(args: TokenStream, input: TokenStream) -> TokenStream {
 /// #[derive(Clone, Command)]
 /// #[command(title = "My command without variants or arguments")]
 /// struct MyCommand;
+/// # fn main() {}
 /// ```
 /// ```
 /// use sync_lsp::workspace::execute_command::Command;
@@ -148,6 +188,7 @@ pub fn type_provider(args: TokenStream, input: TokenStream) -> TokenStream {
 ///     #[command(title = "My command with arguments")]
 ///     MyCommandWithArguments(u32),
 /// }
+/// # fn main() {}
 /// ```
 #[proc_macro_derive(Command, attributes(command))]
 pub fn command(input: TokenStream) -> TokenStream {
@@ -179,7 +220,139 @@ pub fn command(input: TokenStream) -> TokenStream {
         Data::Union(..) => panic!("Command macro cannot be implemented on unions"),
     }
         .unwrap_or_else(|err| err.to_compile_error().into())
-        .into()
+}
+
+/// This macro implements the [`TelemetryEvent`] trait for a given type.
+/// Structs and enums with named fields or without fields are supported and all fields must implement `Serialize`.
+/// The `#[telemetry(name = "...")]` attribute can be used to define the name of an event
+/// on enum variants or structs, which defaults to their identifier.
+/// 
+/// # Example
+/// ```
+/// use sync_lsp::window::telemetry::TelemetryEvent;
+/// 
+/// #[derive(TelemetryEvent)]
+/// enum MyTelemetryEvent {
+///     #[telemetry(name = "indexed")]
+///     Indexed { files: usize },
+///     Restarted
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_derive(TelemetryEvent, attributes(telemetry))]
+pub fn telemetry_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let events = match input.data {
+        Data::Struct(data) => vec![(input.attrs, parse_quote!(Self), input.ident.clone(), data.fields)],
+        Data::Enum(data) => {
+            for attr in input.attrs.iter() {
+                let Meta::List(list) = &attr.meta else { continue };
+                if list.path.is_ident("telemetry") {
+                    return Error::new(
+                        attr.span(), 
+                        "The telemetry attribute is not supported in this position"
+                    ).to_compile_error().into()
+                }
+            }
+
+            data.variants.into_iter().map(|variant| {
+                let ident = variant.ident;
+                (variant.attrs, parse_quote!(Self::#ident), ident, variant.fields)
+            }).collect()
+        },
+        Data::Union(..) => panic!("TelemetryEvent macro cannot be implemented on unions"),
+    };
+
+    impl_telemetry_event(input.ident, input.generics, events)
+        .unwrap_or_else(|err| err.to_compile_error().into())
+}
+
+fn impl_telemetry_event(ident: Ident, generics: Generics, events: Vec<(Vec<Attribute>, Path, Ident, Fields)>) -> Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut names = Vec::new();
+    let mut paths = Vec::new();
+    let mut fields = Vec::new();
+    let mut keys = Vec::new();
+
+    for (attrs, path, ident, event_fields) in events {
+        names.push(event_name(&attrs, ident.unraw().to_string())?);
+        paths.push(path);
+
+        let idents = match event_fields {
+            Fields::Named(named) => named.named.into_iter().filter_map(|field| field.ident).collect(),
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(unnamed) => return Err(Error::new(
+                unnamed.span(),
+                "The TelemetryEvent macro only supports named fields"
+            ))
+        };
+
+        keys.push(idents.iter().map(|ident| ident.unraw().to_string()).collect::<Vec<_>>());
+        fields.push(idents);
+    }
+
+    let lengths = fields.iter().map(Vec::len);
+
+    Ok(quote!(
+        impl #impl_generics sync_lsp::window::telemetry::TelemetryEvent for #ident #ty_generics #where_clause {
+            fn schema() -> Vec<(&'static str, Vec<&'static str>)> {
+                vec![#((#names, vec![#(#keys),*])),*]
+            }
+
+            fn name(&self) -> &'static str {
+                match *self {
+                    #(#paths { .. } => #names,)*
+                }
+            }
+
+            fn serialize_fields<__S__: serde::ser::Serializer>(&self, serializer: __S__) -> Result<__S__::Ok, __S__::Error> {
+                use serde::ser::SerializeMap;
+                match *self {
+                    #(#paths { #(ref #fields,)* } => {
+                        let mut map = serializer.serialize_map(Some(#lengths))?;
+                        #(map.serialize_entry(#keys, #fields)?;)*
+                        map.end()
+                    })*
+                }
+            }
+        }
+    ).into())
+}
+
+fn event_name(attrs: &[Attribute], default: String) -> Result<String> {
+    let mut name = default;
+
+    for attribute in attrs.iter() {
+        let span = attribute.span();
+        let Meta::List(list) = &attribute.meta else { continue };
+        if !list.path.is_ident("telemetry") { continue };
+        let pair: ExprAssign = list.parse_args()?;
+
+        let Expr::Path(ExprPath { path, .. }) = *pair.left else {
+            return Err(Error::new(
+                span, 
+                "Expected a path for the left side of the telemetry attribute")
+            );
+        };
+
+        if !path.is_ident("name") {
+            return Err(Error::new(
+                span, 
+                "The telemetry attribute only supports the name field")
+            );
+        };
+
+        let Expr::Lit(ExprLit { lit: Lit::Str(literal), .. }) = *pair.right else {
+            return Err(Error::new(
+                span, 
+                "Expected a string literal for the right side of the telemetry attribute")
+            );
+        };
+
+        name = literal.value();
+    }
+
+    Ok(name)
 }
 
 fn impl_enum(ident: Ident, generics: Generics, variants: Vec<Variant>, segment: Option<PathSegment>) -> Result<TokenStream> {
@@ -7,7 +7,7 @@ use sync_lsp::{
 
 use sync_lsp::window::{
     MessageType,
-    show_message_request::MessageActionItem
+    show_message_request::{MessageActionItem, ShowMessageResponse}
 };
 
 // The state of the server, in this case it's empty,
@@ -44,20 +44,22 @@ impl MyServerState {
             vec![
                 MessageActionItem {
                     title: "Action 1".to_string(),
-                    data: document.uri.clone()
+                    data: document.uri.to_string()
                 },
                 MessageActionItem {
                     title: "Action 2".to_string(),
-                    data: document.uri.clone()
+                    data: document.uri.to_string()
                 }
             ]
         );
     }
 
-    fn on_show_message_response(server: &mut Server<Self>, item: MessageActionItem<String>) {
-        server.connection.show_message(
-            MessageType::Info,
-            format!("Performing {} on {}", item.title, item.data)
-        );
+    fn on_show_message_response(server: &mut Server<Self>, response: ShowMessageResponse<String>) {
+        if let ShowMessageResponse::Selected(item) = response {
+            server.connection.show_message(
+                MessageType::Info,
+                format!("Performing {} on {}", item.title, item.data)
+            );
+        }
     }
 }
\ No newline at end of file
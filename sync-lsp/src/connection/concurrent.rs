@@ -0,0 +1,47 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use crate::{Server, TypeProvider};
+use super::{Callback, Workers};
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Enables a pool of worker threads for request handlers registered via the `on_*_concurrent` methods,
+    /// like [`Server::on_hover_concurrent`]. These handlers only receive a snapshot of the server state,
+    /// which is cloned on the server thread right before the handler is dispatched to a worker.
+    /// Therefore it is recommended to keep expensive data behind an [`Arc`](std::sync::Arc).
+    /// 
+    /// Notifications and all other requests are still processed in order on the thread running the server,
+    /// which is also the only thread writing responses to the transport.
+    /// Responses of concurrent handlers are sent as soon as they have been computed, so they may be out of order.
+    /// If the transport can't be polled, they are only sent while processing the next message.
+    /// 
+    /// Changing the number of threads doesn't affect requests, which have already been dispatched to a worker,
+    /// and all of them are answered before [`Server::serve`] returns after the `exit` notification.
+    ///
+    /// # Argument
+    /// * `count` - The number of worker threads. If it is zero, concurrent handlers are run on the server thread.
+    pub fn set_worker_threads(&mut self, count: usize) {
+        if let Some(workers) = self.connection.workers.as_mut() {
+            return workers.resize(count)
+        }
+
+        if count == 0 { return }
+
+        #[cfg(feature = "mio")]
+        let waker = self.connection.transport.waker();
+
+        self.connection.workers = Some(Workers::new(count, move || {
+            #[cfg(feature = "mio")]
+            if let Some(waker) = waker.as_ref() {
+                waker.wake().ok();
+            }
+        }));
+    }
+}
+
+impl<T: TypeProvider + Clone + Send> Server<T> {
+    pub(crate) fn concurrent<P, R>(callback: impl 'static + Fn(&T, P) -> R + Send + Copy) -> Callback<Server<T>>
+        where P: 'static + DeserializeOwned + Send, R: 'static + Serialize {
+        Callback::concurrent(|server: &mut Server<T>| server.state.clone(), callback)
+    }
+}
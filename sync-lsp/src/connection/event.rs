@@ -0,0 +1,122 @@
+use std::sync::mpsc::{channel, Sender, Receiver};
+#[cfg(feature = "mio")]
+use std::sync::Arc;
+#[cfg(feature = "mio")]
+use mio::Waker;
+use crate::{Server, TypeProvider};
+
+/// A handle which may be used to send [`TypeProvider::UserEvent`]s to the server from other threads.
+/// The events are processed on the thread running the server, by the callback set via [`Server::on_user_event`].
+/// 
+/// # Example
+/// ```
+/// use std::thread;
+/// use sync_lsp::{Transport, TypeProvider, Server};
+/// 
+/// struct MyServerState;
+/// 
+/// #[sync_lsp::type_provider]
+/// impl TypeProvider for MyServerState {
+///     type UserEvent = String;
+/// }
+/// 
+/// fn main() {
+///     let transport = Transport::stdio();
+///     let mut server = Server::new(MyServerState, transport);
+///     let sender = server.event_sender();
+/// 
+///     server.on_user_event(|server, message| {
+///         server.connection.show_message(sync_lsp::window::MessageType::Info, message);
+///     });
+/// 
+///     thread::spawn(move || {
+///         // Do some background work here.
+///         sender.send("Background work finished".to_string());
+///     });
+/// 
+///     server.serve().unwrap();
+/// }
+/// ```
+pub struct EventSender<E> {
+    sender: Sender<E>,
+    #[cfg(feature = "mio")]
+    waker: Option<Arc<Waker>>
+}
+
+pub(crate) struct UserEvents<T: TypeProvider> {
+    sender: Sender<T::UserEvent>,
+    receiver: Receiver<T::UserEvent>,
+    callback: fn(&mut Server<T>, T::UserEvent)
+}
+
+impl<E: Send> EventSender<E> {
+
+    /// Sends an event to the server and wakes it up, if it is waiting for messages.
+    /// 
+    /// # Argument
+    /// * `event` - The event to send.
+    /// * `return` - A boolean indicating whether the event was sent, which is only false if the server has been dropped.
+    pub fn send(&self, event: E) -> bool {
+        if self.sender.send(event).is_err() {
+            return false
+        }
+
+        #[cfg(feature = "mio")]
+        if let Some(waker) = self.waker.as_ref() {
+            waker.wake().ok();
+        }
+
+        true
+    }
+}
+
+impl<E> Clone for EventSender<E> {
+    fn clone(&self) -> Self {
+        EventSender {
+            sender: self.sender.clone(),
+            #[cfg(feature = "mio")]
+            waker: self.waker.clone()
+        }
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Returns a new [`EventSender`], which may be moved to other threads.
+    /// If the transport can be polled, sending an event will wake up the server immediately.
+    /// Otherwise the event is processed as soon as the next message from the client has been handled.
+    pub fn event_sender(&mut self) -> EventSender<T::UserEvent> {
+        EventSender {
+            sender: self.user_events.sender.clone(),
+            #[cfg(feature = "mio")]
+            waker: self.connection.transport.waker()
+        }
+    }
+
+    /// Sets the callback that will be called for every event sent through an [`EventSender`].
+    /// 
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as an event is received:
+    ///     * The server instance receiving the event.
+    ///     * The [`TypeProvider::UserEvent`] which has been sent.
+    pub fn on_user_event(&mut self, callback: fn(&mut Server<T>, T::UserEvent)) {
+        self.user_events.callback = callback;
+    }
+
+    pub(super) fn dispatch_user_event(&mut self) -> bool {
+        let Ok(event) = self.user_events.receiver.try_recv() else { return false };
+        (self.user_events.callback)(self, event);
+        true
+    }
+}
+
+impl<T: TypeProvider> Default for UserEvents<T> {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        UserEvents {
+            sender,
+            receiver,
+            callback: |_, _| ()
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::fs::File;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Sender, Receiver};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// A logger for the [`log`] crate, which forwards records to the client
+/// via [`Connection::log_message`](crate::Connection::log_message)
+/// and optionally writes them to a file.
+/// 
+/// By default it is installed as the global logger once the server starts.
+/// If another logger should be used alongside it, the automatic installation can be disabled via
+/// [`Server::set_global_logger`](crate::Server::set_global_logger) and the logger obtained by
+/// [`Server::logger`](crate::Server::logger) can be called from a custom [`Log`] implementation.
+/// All clones share the same level filter and file.
+#[derive(Clone)]
+pub struct ClientLogger {
+    sender: Sender<(Level, String)>,
+    level: Arc<AtomicUsize>,
+    file: Arc<Mutex<Option<File>>>
+}
+
+/// The receiving end of a [`ClientLogger`], which is owned by the connection.
+pub(crate) struct Logging {
+    receiver: Receiver<(Level, String)>,
+    logger: ClientLogger,
+    pub(crate) global: bool,
+    pub(crate) attempted: bool,
+    pub(crate) installed: bool
+}
+
+impl ClientLogger {
+    pub(crate) fn level(&self) -> LevelFilter {
+        LevelFilter::iter()
+            .nth(self.level.load(Ordering::Relaxed))
+            .unwrap_or(LevelFilter::Off)
+    }
+
+    pub(crate) fn set_level(&self, level: LevelFilter) {
+        self.level.store(level as usize, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_file(&self, file: Option<File>) {
+        if let Ok(mut current) = self.file.lock() {
+            *current = file;
+        }
+    }
+}
+
+impl Log for ClientLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) { return }
+
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                writeln!(file, "[{}] {}", record.level(), record.args()).ok();
+            }
+        }
+
+        self.sender.send((record.level(), record.args().to_string())).ok();
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                file.flush().ok();
+            }
+        }
+    }
+}
+
+impl Logging {
+    pub(crate) fn logger(&self) -> &ClientLogger {
+        &self.logger
+    }
+
+    pub(crate) fn try_recv(&self) -> Option<(Level, String)> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Default for Logging {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+
+        Logging {
+            receiver,
+            logger: ClientLogger {
+                sender,
+                #[cfg(debug_assertions)]
+                level: Arc::new(AtomicUsize::new(LevelFilter::Trace as usize)),
+                #[cfg(not(debug_assertions))]
+                level: Arc::new(AtomicUsize::new(LevelFilter::Info as usize)),
+                file: Arc::new(Mutex::new(None))
+            },
+            global: true,
+            attempted: false,
+            installed: false
+        }
+    }
+}
@@ -4,9 +4,10 @@ use serde::de::{Visitor, MapAccess, Deserializer, Error as SerdeError};
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 
+/// The id of a request, which is also used by the corresponding response.
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq)]
 #[serde(untagged)]
-pub(crate) enum MessageID {
+pub enum MessageID {
     Integer(u64),
     String(String),
     Null
@@ -22,22 +23,45 @@ pub(crate) struct EmptyParams {
     
 }
 
+/// The level of detail of `$/logTrace` notifications, which is set by the client.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceValue {
+    /// No traces are sent.
+    #[default]
+    Off,
+    /// Only trace messages are sent.
+    Messages,
+    /// Trace messages are sent along with verbose information.
+    Verbose
+}
+
+#[derive(Serialize)]
+pub(crate) struct LogTraceParams {
+    pub(crate) message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) verbose: Option<String>
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub(super) enum Version {
     #[serde(rename = "2.0")]
     Current
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-pub(crate) struct Error {
+/// An error, which has been returned by the other side in response to a request.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Error {
+	/// The error code, which indicates the kind of error.
 	pub code: ErrorCode,
+	/// A short description of the error.
 	pub message: String,
 	//pub data: T
 }
 
 /// Error codes used to either return a response or log to the client.
 #[repr(i32)]
-#[derive(Serialize_repr, Deserialize_repr, Debug, PartialEq)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorCode {
 	// Defined by initialize
 	UnknownProtocolVersion = 1,
@@ -164,10 +188,12 @@ impl<'a> Visitor<'a> for MessageVisitor {
             return Err(SerdeError::missing_field("jsonrpc"))
         };
 
-        if let Some(params) = params {
+        if let Some(method) = method {
             let fields = &["jsonrpc", "id", "method", "params"];
             
-            let Some(method) = method else { return Err(SerdeError::missing_field("method")) };
+            // Params may be omitted according to JSON-RPC 2.0, which clients do for requests
+            // and notifications without parameters, like `shutdown` and `exit`.
+            let params = params.unwrap_or(Value::Null);
             if error.is_some() { return Err(SerdeError::unknown_field("error", fields)) }
             if result.is_some() { return Err(SerdeError::unknown_field("result", fields)) }
 
@@ -0,0 +1,172 @@
+use serde_json::Value;
+use super::message::{Message as RpcMessage, MessageID, Version, Error as RpcError, ErrorCode};
+
+/// A json-rpc message, which is passed through all [`Middleware`]s before it is dispatched or sent.
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// A request, which expects a response.
+    Request {
+        id: MessageID,
+        method: String,
+        params: Value
+    },
+    /// A notification, which doesn't expect a response.
+    Notification {
+        method: String,
+        params: Value
+    },
+    /// A successful response to a request.
+    Response {
+        id: MessageID,
+        result: Value
+    },
+    /// A failed response to a request.
+    Error {
+        id: MessageID,
+        error: RpcError
+    }
+}
+
+/// Middleware may be used to inspect and transform all messages exchanged with the client,
+/// which can be added to a server via [`Server::add_middleware`](crate::Server::add_middleware).
+/// Incoming messages pass through all middleware in the order in which they were added
+/// before they are dispatched, while outgoing messages pass through them in reverse order before they are sent.
+/// Note that [`Connection::cancelled`](crate::Connection::cancelled) looks ahead at queued notifications,
+/// before they have passed through the middleware.
+///
+/// # Example
+/// ```
+/// use sync_lsp::{Transport, TypeProvider, Server, Middleware, Message, ResponseError, ErrorCode};
+///
+/// struct Authorization;
+///
+/// impl Middleware for Authorization {
+///     fn incoming(&mut self, message: Message) -> Result<Option<Message>, ResponseError> {
+///         match &message {
+///             Message::Request { method, .. } if method.starts_with("custom/") => Err(ResponseError {
+///                 code: ErrorCode::RequestFailed,
+///                 message: "Unauthorized".to_string()
+///             }),
+///             _ => Ok(Some(message))
+///         }
+///     }
+/// }
+///
+/// struct MyServerState;
+///
+/// #[sync_lsp::type_provider]
+/// impl TypeProvider for MyServerState {}
+///
+/// fn main() {
+///     let transport = Transport::stdio();
+///     let mut server = Server::new(MyServerState, transport);
+///     server.add_middleware(Authorization);
+///     server.serve().unwrap();
+/// }
+/// ```
+pub trait Middleware: 'static {
+    /// Called for every message received from the client before it is dispatched.
+    ///
+    /// # Argument
+    /// * `message` - The received message.
+    /// * `return` - The message, which should be passed on, `None` to drop it or an error,
+    ///   which is sent as the response if the message is a request and logged otherwise.
+    ///   Since every request has to be answered, dropping a request answers it with [`ErrorCode::RequestCancelled`].
+    fn incoming(&mut self, message: Message) -> Result<Option<Message>, RpcError> {
+        Ok(Some(message))
+    }
+
+    /// Called for every message before it is sent to the client.
+    ///
+    /// # Argument
+    /// * `message` - The message to send.
+    /// * `return` - The message, which should be passed on or `None` to drop it.
+    ///   Since every request has to be answered, a dropped response is replaced by an [`ErrorCode::RequestCancelled`] error,
+    ///   which is sent without passing through the remaining middleware.
+    fn outgoing(&mut self, message: Message) -> Option<Message> {
+        Some(message)
+    }
+}
+
+/// The middleware chain of a connection.
+#[derive(Default)]
+pub(crate) struct Middlewares {
+    chain: Vec<Box<dyn Middleware>>
+}
+
+impl Middlewares {
+    pub(crate) fn push(&mut self, middleware: impl Middleware) {
+        self.chain.push(Box::new(middleware));
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.chain.is_empty()
+    }
+
+    /// Passes a received message through the chain. Dropped requests are turned into an error,
+    /// so that they are answered like rejected ones.
+    pub(super) fn incoming(&mut self, message: RpcMessage) -> Result<Option<RpcMessage>, RpcError> {
+        let request = matches!(message, RpcMessage::Request { .. });
+        let mut message = message.into();
+        for middleware in self.chain.iter_mut() {
+            match middleware.incoming(message)? {
+                Some(next) => message = next,
+                None if request => return Err(dropped("Request")),
+                None => return Ok(None)
+            }
+        }
+        Ok(Some(message.into()))
+    }
+
+    /// Passes a message through the chain before it is sent. Dropped responses are replaced by an error,
+    /// so that the client doesn't wait for them forever.
+    pub(super) fn outgoing(&mut self, message: RpcMessage) -> Option<RpcMessage> {
+        let id = match &message {
+            RpcMessage::Response { id, .. } | RpcMessage::Error { id, .. } => Some(id.clone()),
+            RpcMessage::Request { .. } | RpcMessage::Notification { .. } => None
+        };
+
+        let mut message = message.into();
+        for middleware in self.chain.iter_mut().rev() {
+            match middleware.outgoing(message) {
+                Some(next) => message = next,
+                None => return id.map(|id| RpcMessage::Error {
+                    jsonrpc: Version::Current,
+                    id,
+                    error: dropped("Response")
+                })
+            }
+        }
+        Some(message.into())
+    }
+}
+
+fn dropped(kind: &str) -> RpcError {
+    RpcError {
+        code: ErrorCode::RequestCancelled,
+        message: format!("{kind} dropped by middleware")
+    }
+}
+
+impl From<RpcMessage> for Message {
+    fn from(message: RpcMessage) -> Self {
+        match message {
+            RpcMessage::Request { id, method, params, .. } => Message::Request { id, method, params },
+            RpcMessage::Notification { method, params, .. } => Message::Notification { method, params },
+            RpcMessage::Response { id, result, .. } => Message::Response { id, result },
+            RpcMessage::Error { id, error, .. } => Message::Error { id, error }
+        }
+    }
+}
+
+impl From<Message> for RpcMessage {
+    fn from(message: Message) -> Self {
+        let jsonrpc = Version::Current;
+        match message {
+            Message::Request { id, method, params } => RpcMessage::Request { jsonrpc, id, method, params },
+            Message::Notification { method, params } => RpcMessage::Notification { jsonrpc, method, params },
+            Message::Response { id, result } => RpcMessage::Response { jsonrpc, id, result },
+            Message::Error { id, error } => RpcMessage::Error { jsonrpc, id, error }
+        }
+    }
+}
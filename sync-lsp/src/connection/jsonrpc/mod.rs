@@ -1,20 +1,46 @@
 use std::rc::Rc;
 use crate::Transport;
-use log::{Level, Log, Metadata, Record, error};
+use log::{Level, error};
 use serde_json::{Value, Error as JsonError, from_value, to_value, from_str};
 use serde::{Serialize, de::DeserializeOwned};
-pub(super) use message::{Error as RpcError, MessageID};
-pub(crate) use message::{EmptyParams, CancelParams};
-pub use message::ErrorCode;
-use std::sync::mpsc::Sender;
+pub(super) use message::Error as RpcError;
+pub use message::Error as ResponseError;
+pub use message::MessageID;
+pub use middleware::{Middleware, Message};
+pub(crate) use middleware::Middlewares;
+pub(crate) use message::{EmptyParams, CancelParams, LogTraceParams};
+pub(crate) use workers::Workers;
+pub(crate) use logger::Logging;
+pub(crate) use notifier::{Notifier, Notifications};
+pub(crate) use queue::MessageQueue;
+pub use message::{ErrorCode, TraceValue};
+pub use logger::ClientLogger;
+use workers::{Job, JobResult, Task};
+use std::time::{Duration, Instant};
 
 mod message;
+mod workers;
+mod logger;
+mod notifier;
+mod middleware;
+mod queue;
+
+/// The outcome of a handled request or notification, which is passed to [`RpcConnection::record`].
+pub(crate) enum Outcome<'a> {
+    Success,
+    Cancelled,
+    Error(ErrorCode, &'a str),
+    Panic(&'a str)
+}
 
 pub(crate) trait RpcResolver: Sized + 'static {
     type Connection: RpcConnection;
 
     fn connection(&mut self) -> &mut Self::Connection;
     fn resolve(&self, method: &str) -> Option<Callback<Self>>;
+    fn exited(&self) -> bool;
+    fn dispatch_event(&mut self) -> bool;
+    fn deadline(&self) -> Option<Instant>;
 }
 
 pub(crate) trait RpcConnection: Sized + 'static {
@@ -22,37 +48,44 @@ pub(crate) trait RpcConnection: Sized + 'static {
     fn take_error(&mut self) -> Option<RpcError>;
     fn log(&mut self, level: Level, message: String);
     fn set_current_request(&mut self, id: Option<MessageID>);
+    fn validate_result(&mut self);
+    fn set_request_context(&mut self, params: &Value);
+    fn logging(&mut self) -> &mut Logging;
+    fn workers(&mut self) -> Option<&mut Workers>;
+    fn trace_value(&self) -> TraceValue;
+    fn trace_messages(&self) -> bool;
+    fn notifications(&mut self) -> &mut Notifications;
+    fn middleware(&mut self) -> &mut Middlewares;
+    fn queue(&mut self) -> &mut MessageQueue;
+    fn params_size(&self, params: &Value) -> usize;
+    fn record(&mut self, method: &str, size: usize, elapsed: Duration, outcome: Outcome);
 
     fn notify(&mut self, method: &str, params: impl Serialize)
         { RpcConnectionImpl::notify(self, method, params) }
     fn request(&mut self, method: &str, tag: impl Serialize, params: impl Serialize) -> bool
         { RpcConnectionImpl::request(self, method, tag, params) }
-    fn peek_notification<T: DeserializeOwned>(&mut self, method: &str) -> Option<T>
-        { RpcConnectionImpl::peek_notification(self, method) }
-}
-
-pub(crate) enum Callback<T: RpcResolver> {
-    Request(Rc<dyn Fn(&mut T, Value) -> Result<Value, JsonError>>),
-    Notification(Rc<dyn Fn(&mut T, Value) -> Result<(), JsonError>>),
-    Response(Rc<dyn Fn(&mut T, String, Option<Value>) -> Result<(), JsonError>>),
-}
-
-struct RpcLogger {
-    sender: Sender<(Level, String)>
+    fn scan_queue(&mut self)
+        { RpcConnectionImpl::scan_queue(self) }
+    fn take_notification<R>(&mut self, accept: impl FnOnce(&str, &Value) -> Option<R>) -> Option<R>
+        { RpcConnectionImpl::take_notification(self, accept) }
+    fn take_responses(&mut self, method: &str) -> Vec<(String, Result<Value, RpcError>)>
+        { RpcConnectionImpl::take_responses(self, method) }
+    fn flush_notifications(&mut self)
+        { RpcConnectionImpl::flush_notifications(self) }
+    fn trace(&mut self, message: String, verbose: impl FnOnce() -> Option<String>)
+        { RpcConnectionImpl::trace(self, message, verbose) }
 }
 
-impl Log for RpcLogger {
-    fn enabled(&self, _: &Metadata) -> bool {
-        true
-    }
+type RequestHandler<T> = Rc<dyn Fn(&mut T, Value) -> Result<Value, JsonError>>;
+type NotificationHandler<T> = Rc<dyn Fn(&mut T, Value) -> Result<(), JsonError>>;
+type ResponseHandler<T> = Rc<dyn Fn(&mut T, String, Result<Value, RpcError>) -> Result<(), JsonError>>;
+type ConcurrentHandler<T> = Rc<dyn Fn(&mut T, Value) -> Result<Job, JsonError>>;
 
-    fn log(&self, record: &Record) {
-        if self.enabled(record.metadata()) {
-            self.sender.send((record.level(), record.args().to_string())).ok();
-        }
-    }
-
-    fn flush(&self) {}
+pub(crate) enum Callback<T: RpcResolver> {
+    Request(RequestHandler<T>),
+    Notification(NotificationHandler<T>),
+    Response(ResponseHandler<T>),
+    Concurrent(ConcurrentHandler<T>),
 }
 
 impl<T: RpcResolver> Clone for Callback<T> {
@@ -61,6 +94,7 @@ impl<T: RpcResolver> Clone for Callback<T> {
             Callback::Request(callback) => Callback::Request(callback.clone()),
             Callback::Notification(callback) => Callback::Notification(callback.clone()),
             Callback::Response(callback) => Callback::Response(callback.clone()),
+            Callback::Concurrent(callback) => Callback::Concurrent(callback.clone()),
         }
     }
 
@@ -78,75 +112,248 @@ impl<T: RpcResolver> Callback<T> {
     pub(crate) fn notification<P: DeserializeOwned>(callback: impl 'static + Fn(&mut T, P)) -> Self {
         Self::Notification(Rc::new(move |server, value| {
             let params = from_value(value)?;
-            Ok(callback(server, params))
+            callback(server, params);
+            Ok(())
+        }))
+    }
+
+    /// Creates a request callback, which is run on a worker thread against a snapshot
+    /// of the server, if worker threads are enabled, and on the current thread otherwise.
+    pub(crate) fn concurrent<S, P, R>(snapshot: impl 'static + Fn(&mut T) -> S, callback: impl 'static + Fn(&S, P) -> R + Send + Copy) -> Self
+        where S: 'static + Send, P: 'static + DeserializeOwned + Send, R: 'static + Serialize {
+        Self::Concurrent(Rc::new(move |server, value| {
+            let params = from_value(value)?;
+            let snapshot = snapshot(server);
+            Ok(Box::new(move || {
+                to_value(callback(&snapshot, params)).map_err(|error| RpcError {
+                    code: ErrorCode::InternalError,
+                    message: format!("Failed to serialize result: {error}")
+                })
+            }))
         }))
     }
 
     pub(crate) fn response<D: DeserializeOwned + Default, P: DeserializeOwned + Default>(callback: impl 'static + Fn(&mut T, D, P)) -> Self {
+        Self::response_result(move |server, id, result: Result<P, RpcError>| {
+            callback(server, id, result.unwrap_or_default())
+        })
+    }
+
+    /// Creates a response callback, which also receives errors returned by the client.
+    pub(crate) fn response_result<D: DeserializeOwned + Default, P: DeserializeOwned>(callback: impl 'static + Fn(&mut T, D, Result<P, RpcError>)) -> Self {
         Self::Response(Rc::new(move |server, id, value| {
             let id = from_str(id.as_str()).unwrap_or_else(|err| {
                 error!("Failed to parse id: {id}: {err}");
                 D::default()
             });
 
-            match value.map(|value| from_value(value)) {
-                Some(Ok(params)) => Ok(callback(server, id, params)),
-                Some(Err(error)) => Err(error),
-                None => Ok(callback(server, id, P::default())),
-            }
+            let result = match value {
+                Ok(value) => Ok(from_value(value)?),
+                Err(error) => Err(error)
+            };
+
+            callback(server, id, result);
+            Ok(())
         }))
     }
 }
 
 #[allow(non_snake_case)]
 pub(super) mod RpcConnectionImpl {
-    use log::{error, set_boxed_logger, set_max_level, LevelFilter};
-    use serde::de::DeserializeOwned;
-    use serde_json::{Value, from_slice, to_string, to_value, from_value};
-    use std::io::{Error, ErrorKind};
-    use std::sync::mpsc::channel;
+    use log::{error, set_boxed_logger, set_max_level, Level};
+    use serde_json::{Value, from_slice, to_string, to_value};
+    use std::io::Error;
+    use std::time::{Duration, Instant};
     use serde::Serialize;
+    use crate::connection::ProcessStatus;
     use crate::connection::jsonrpc::message::{Message, MessageID, Version, Error as RpcError};
 
     use super::message::ErrorCode;
-    use super::{RpcConnection, Callback, RpcLogger, RpcResolver};
+    use super::{RpcConnection, Callback, ConcurrentHandler, RpcResolver, JobResult, Task, Outcome, TraceValue, LogTraceParams, MessageQueue};
+    use super::workers::panic_message;
+    use std::any::Any;
+    use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+
+    pub(crate) fn serve<R: RpcResolver>(mut server: R) -> Result<R, Error> {
+        loop {
+            if let ProcessStatus::Stopped = process(&mut server, None)? {
+                return Ok(server)
+            }
+        }
+    }
 
-    pub(crate) fn serve(mut server: impl RpcResolver) -> Result<(), Error> {
+    pub(crate) fn process(server: &mut impl RpcResolver, timeout: Option<Duration>) -> Result<ProcessStatus, Error> {
+        install_logger(server.connection());
+
+        if server.exited() {
+            return Ok(ProcessStatus::Stopped)
+        }
 
-        let (sender, receiver) = channel();
-        let logger = RpcLogger {
-            sender
+        let status = if dispatch_event(server) {
+            ProcessStatus::Processed
+        } else if !wait(server, timeout) {
+            if dispatch_event(server) {
+                ProcessStatus::Processed
+            } else {
+                ProcessStatus::TimedOut
+            }
+        } else if let Some(message) = recv(server.connection()) {
+            handle(server, message);
+            if server.exited() {
+                finish_tasks(server.connection());
+                ProcessStatus::Stopped
+            } else {
+                ProcessStatus::Processed
+            }
+        } else {
+            ProcessStatus::Stopped
         };
-        
-        if let Err(error) = set_boxed_logger(Box::new(logger)) {
-            return Err(Error::new(ErrorKind::Other, error.to_string()));
+
+        flush_log(server.connection());
+        flush_notifications(server.connection());
+
+        if let Some(error) = server.connection().transport().error().take() {
+            Err(error)
+        } else {
+            Ok(status)
         }
+    }
 
-        #[cfg(debug_assertions)]
-        set_max_level(LevelFilter::Trace);
-        #[cfg(not(debug_assertions))]
-        set_max_level(LevelFilter::Info);
+    fn wait(server: &mut impl RpcResolver, timeout: Option<Duration>) -> bool {
+        let timeout = match (timeout, server.deadline()) {
+            (timeout, None) => timeout,
+            (None, Some(deadline)) => Some(deadline.saturating_duration_since(Instant::now())),
+            (Some(timeout), Some(deadline)) => Some(timeout.min(deadline.saturating_duration_since(Instant::now())))
+        };
 
-        while let Some(message) = recv(server.connection()) {
-            handle(&mut server, message);
-            while let Ok((level, message)) = receiver.try_recv() {
-                server.connection().log(level, message);
-            }
+        server.connection().transport().wait(timeout)
+    }
+
+    fn dispatch_event(server: &mut impl RpcResolver) -> bool {
+        if let Some((task, result)) = server.connection().workers().and_then(|workers| workers.try_recv()) {
+            finish_task(server.connection(), task, result);
+            return true
         }
 
-        if let Some(error) = server.connection().transport().error().take() {
-            return Err(error);
+        if !server.dispatch_event() {
+            return false
+        }
+
+        if let Some(error) = server.connection().take_error() {
+            error!("Failed to process event: {}", error.message);
+        }
+
+        true
+    }
+
+    fn install_logger(connection: &mut impl RpcConnection) {
+        let logging = connection.logging();
+        if !logging.global || logging.attempted { return }
+        logging.attempted = true;
+
+        let logger = logging.logger().clone();
+        let level = logger.level();
+
+        if set_boxed_logger(Box::new(logger)).is_ok() {
+            connection.logging().installed = true;
+            set_max_level(level);
         } else {
-            Ok(())
+            connection.log(Level::Warn, "A global logger has already been installed, log records won't be forwarded to the client".to_string());
+        }
+    }
+
+    fn flush_log(connection: &mut impl RpcConnection) {
+        while let Some((level, message)) = connection.logging().try_recv() {
+            connection.log(level, message);
+        }
+    }
+
+    pub(super) fn flush_notifications(connection: &mut impl RpcConnection) {
+        while let Some((method, params)) = connection.notifications().try_recv() {
+            notify(connection, method, params);
+        }
+    }
+
+    /// Buffers all messages, which have been received in the meantime, and updates the [`MessageQueue`],
+    /// which is only parsed again if the buffered messages have changed.
+    pub(super) fn scan_queue(connection: &mut impl RpcConnection) {
+        while connection.transport().peek().is_some() {}
+
+        let generation = connection.transport().generation();
+        if connection.queue().current(generation) { return }
+
+        let queue = MessageQueue::parse(generation, connection.transport().buffered());
+        *connection.queue() = queue;
+    }
+
+    /// Removes the next received notification, if it has been accepted, so that it won't be dispatched.
+    /// The notification is traced and recorded, as if it had been handled by the caller.
+    /// Since it bypasses the middleware, nothing is taken while middleware is installed.
+    pub(super) fn take_notification<R>(connection: &mut impl RpcConnection, accept: impl FnOnce(&str, &Value) -> Option<R>) -> Option<R> {
+        if !connection.middleware().is_empty() {
+            return None
+        }
+
+        if connection.transport().buffered().next().is_none() {
+            connection.transport().peek();
+        }
+
+        let data = connection.transport().buffered().next()?;
+        let Ok(Message::Notification { method, params, .. }) = from_slice(data) else {
+            return None
+        };
+
+        let accepted = accept(&method, &params)?;
+        connection.transport().discard_buffered();
+
+        let size = connection.params_size(&params);
+
+        let trace = start_trace(connection, &params);
+        finish_trace(connection, trace, || format!("Merged notification '{method}'"));
+        connection.record(&method, size, Duration::ZERO, Outcome::Success);
+        Some(accepted)
+    }
+
+    /// Removes all received responses to requests of the given method, which haven't been handled yet,
+    /// and returns their tags along with their results, so that they can be handled by the caller.
+    /// Only responses, which are part of the last [scanned](scan_queue) queue, are taken into account.
+    /// Since they bypass the middleware, nothing is taken while middleware is installed.
+    pub(super) fn take_responses(connection: &mut impl RpcConnection, method: &str) -> Vec<(String, Result<Value, RpcError>)> {
+        if !connection.middleware().is_empty() {
+            return Vec::new()
+        }
+
+        let prefix = format!("{method}#");
+        if !connection.queue().has_response(&prefix) {
+            return Vec::new()
         }
+
+        let matches = |id: &MessageID| matches!(id, MessageID::String(id) if id.starts_with(&prefix));
+        connection.transport().take_buffered(|data| match from_slice(data) {
+            Ok(Message::Response { id, .. } | Message::Error { id, .. }) => matches(&id),
+            _ => false
+        })
+            .into_iter()
+            .filter_map(|data| match from_slice(data.as_slice()) {
+                Ok(Message::Response { id: MessageID::String(id), result, .. }) => Some((id, Ok(result))),
+                Ok(Message::Error { id: MessageID::String(id), error, .. }) => Some((id, Err(error))),
+                _ => None
+            })
+            .map(|(id, result)| (id[prefix.len()..].to_string(), result))
+            .collect()
     }
 
-    pub(super) fn peek_notification<T: DeserializeOwned>(connection: &mut impl RpcConnection, target: &str) -> Option<T> {
-        let data = connection.transport().peek()?;
-        let message = from_slice(data.as_slice()).ok()?;
-        let Message::Notification { method, params, .. } = message else { return None };
-        if method != target { return None };
-        from_value(params).ok()
+    pub(super) fn trace(connection: &mut impl RpcConnection, message: String, verbose: impl FnOnce() -> Option<String>) {
+        let verbose = match connection.trace_value() {
+            TraceValue::Off => return,
+            TraceValue::Messages => None,
+            TraceValue::Verbose => verbose()
+        };
+
+        connection.notify("$/logTrace", LogTraceParams {
+            message,
+            verbose
+        });
     }
 
     pub(super) fn notify(connection: &mut impl RpcConnection, method: &str, params: impl Serialize) {
@@ -205,6 +412,14 @@ pub(super) mod RpcConnectionImpl {
     }
 
     fn send(connection: &mut impl RpcConnection, message: Message) -> bool {
+        let message = match connection.middleware().is_empty() {
+            true => message,
+            false => match connection.middleware().outgoing(message) {
+                Some(message) => message,
+                None => return true
+            }
+        };
+
         match to_string(&message) {
             Ok(message) => {
                 connection.transport().send(message);
@@ -219,14 +434,48 @@ pub(super) mod RpcConnectionImpl {
 
     fn handle(server: &mut impl RpcResolver, message: Message) {
 
+        let message = match server.connection().middleware().is_empty() {
+            true => message,
+            false => match intercept(server.connection(), message) {
+                Some(message) => message,
+                None => return
+            }
+        };
+
         match message {
-            Message::Request { id, params, method, .. } => handle_request(server, method, id, params),
-            Message::Notification { params, method, .. } => handle_notification(server, method, params),
+            Message::Request { id, params, method, .. } => {
+                let size = server.connection().params_size(&params);
+                handle_request(server, method, id, params, size)
+            },
+            Message::Notification { params, method, .. } => {
+                let size = server.connection().params_size(&params);
+                handle_notification(server, method, params, size)
+            },
             Message::Response { id, result, .. } => handle_result(server, id, result),
             Message::Error { id, error, .. } => handle_error(server, id, error),
         }
     }
 
+    /// Passes an incoming message through the middleware and answers requests, which have been rejected.
+    fn intercept(connection: &mut impl RpcConnection, message: Message) -> Option<Message> {
+        let (id, method) = match &message {
+            Message::Request { id, method, .. } => (Some(id.clone()), method.clone()),
+            Message::Notification { method, .. } => (None, method.clone()),
+            Message::Response { id, .. } | Message::Error { id, .. } => (None, format!("response to {id:?}"))
+        };
+
+        match connection.middleware().incoming(message) {
+            Ok(message) => message,
+            Err(error) => {
+                match id {
+                    Some(id) => send_result(connection, id, Err(error)),
+                    None => error!("Rejected {method}: {}", error.message)
+                }
+                None
+            }
+        }
+    }
+
     fn handle_result(server: &mut impl RpcResolver, id: MessageID, result: Value) {
         let MessageID::String(id) = id else {
             return error!("Response without request: {id:?}")
@@ -242,17 +491,17 @@ pub(super) mod RpcConnectionImpl {
 
         let handler = match handler {
             Callback::Response(handler) => handler,
-            Callback::Request(..) | Callback::Notification(..) => return error!("{method} is not a response endpoint"),
+            Callback::Request(..) | Callback::Notification(..) | Callback::Concurrent(..) => return error!("{method} is not a response endpoint"),
         };
 
-        let result = handler(server, tag.to_string(), Some(result));
+        let result = handler(server, tag.to_string(), Ok(result));
 
         if let Some(error) = server.connection().take_error() {
             return error!("Failed to process {method}#{tag}: {}", error.message);
         }
 
         if let Err(error) = result {
-            return error!("Failed to parse result for {method}#{tag}: {}", error);
+            error!("Failed to parse result for {method}#{tag}: {}", error);
         }
     }
 
@@ -271,21 +520,21 @@ pub(super) mod RpcConnectionImpl {
 
         let handler = match handler {
             Callback::Response(handler) => handler,
-            Callback::Request(..) | Callback::Notification(..) => return error!("{method} is not a response endpoint"),
+            Callback::Request(..) | Callback::Notification(..) | Callback::Concurrent(..) => return error!("{method} is not a response endpoint"),
         };
 
         if error.code != ErrorCode::RequestCancelled {
             error!("Error({:?}) for {method}#{tag}: {}", error.code, error.message);
         }
         
-        handler(server, tag.to_string(), None).ok();
+        handler(server, tag.to_string(), Err(error)).ok();
 
         if let Some(error) = server.connection().take_error() {
-            return error!("Failed to process {method}#{tag}: {}", error.message);
+            error!("Failed to process {method}#{tag}: {}", error.message);
         }
     }
 
-    fn handle_notification(server: &mut impl RpcResolver, method: String, params: Value) {
+    fn handle_notification(server: &mut impl RpcResolver, method: String, params: Value, size: usize) {
 
         let Some(handler) = server.resolve(method.as_str()) else {
             return error!("Method not found: {method}")
@@ -293,21 +542,32 @@ pub(super) mod RpcConnectionImpl {
 
         let handler = match handler {
             Callback::Notification(handler) => handler,
-            Callback::Request(..) | Callback::Response(..) => return error!("{method} is not a notification endpoint"),
+            Callback::Request(..) | Callback::Response(..) | Callback::Concurrent(..) => return error!("{method} is not a notification endpoint"),
         };
 
-        let result = handler(server, params);
+        let start = Instant::now();
+        let trace = start_trace(server.connection(), &params);
+        let result = catch_unwind(AssertUnwindSafe(|| handler(server, params)))
+            .unwrap_or_else(|payload| panicked(server.connection(), &method, size, start, payload));
+        finish_trace(server.connection(), trace, || format!("Handled notification '{method}'"));
+
+        let result = match (server.connection().take_error(), result) {
+            (Some(error), _) => Err(error),
+            (None, Ok(())) => Ok(()),
+            (None, Err(error)) => Err(RpcError {
+                code: ErrorCode::InvalidParams,
+                message: format!("Failed to parse params: {error}")
+            })
+        };
 
-        if let Some(error) = server.connection().take_error() {
-            return error!("Failed to process {method}: {}", error.message);
-        }
+        record(server.connection(), &method, size, start, result.as_ref().map(|_| ()));
 
         if let Err(error) = result {
-            return error!("Failed to parse params for {method}: {}", error);
+            error!("Failed to process {method}: {}", error.message);
         }
     }
 
-    fn handle_request(server: &mut impl RpcResolver, method: String, id: MessageID, params: Value) {
+    fn handle_request(server: &mut impl RpcResolver, method: String, id: MessageID, params: Value, size: usize) {
         let Some(handler) = server.resolve(method.as_str()) else {
             send(server.connection(), Message::Error {
                 jsonrpc: Version::Current,
@@ -323,6 +583,12 @@ pub(super) mod RpcConnectionImpl {
 
         let handler = match handler {
             Callback::Request(handler) => handler,
+            Callback::Concurrent(handler) => return handle_concurrent(server, handler, Task {
+                id,
+                method,
+                size,
+                start: Instant::now()
+            }, params),
             Callback::Notification(..) | Callback::Response(..) => {
                 send(server.connection(), Message::Error {
                     jsonrpc: Version::Current,
@@ -337,34 +603,130 @@ pub(super) mod RpcConnectionImpl {
             }
         };
 
+        let start = Instant::now();
+        let trace = start_trace(server.connection(), &params);
         server.connection().set_current_request(Some(id.clone()));
-        let result = handler(server, params);
+        server.connection().set_request_context(&params);
+        let result = catch_unwind(AssertUnwindSafe(|| handler(server, params)))
+            .unwrap_or_else(|payload| panicked(server.connection(), &method, size, start, payload));
+        server.connection().validate_result();
         server.connection().set_current_request(None);
+        // Progress and partial results have to arrive before the response.
+        server.connection().flush_notifications();
+        finish_trace(server.connection(), trace, || format!("Handled request '{method} - ({})'", to_string(&id).unwrap_or_default()));
+
+        let result = match (server.connection().take_error(), result) {
+            (Some(error), _) => Err(error),
+            (None, Ok(result)) => Ok(result),
+            (None, Err(error)) => Err(RpcError {
+                code: ErrorCode::InvalidParams,
+                message: format!("Failed to parse params: {error}"),
+            })
+        };
 
-        if let Some(error) = server.connection().take_error() {
-            send(server.connection(), Message::Error {
-                jsonrpc: Version::Current,
-                id,
-                error
-            });
+        record(server.connection(), &method, size, start, result.as_ref().map(|_| ()));
+        send_result(server.connection(), id, result);
+    }
 
-            return
+    fn record(connection: &mut impl RpcConnection, method: &str, size: usize, start: Instant, result: Result<(), &RpcError>) {
+        connection.record(method, size, start.elapsed(), match result {
+            Ok(()) => Outcome::Success,
+            Err(error) if error.code == ErrorCode::RequestCancelled => Outcome::Cancelled,
+            Err(error) => Outcome::Error(error.code, &error.message)
+        });
+    }
+
+    /// Records a panic of a handler on the current thread, before continuing to unwind.
+    fn panicked(connection: &mut impl RpcConnection, method: &str, size: usize, start: Instant, payload: Box<dyn Any + Send>) -> ! {
+        let message = panic_message(payload.as_ref());
+        connection.record(method, size, start.elapsed(), Outcome::Panic(&message));
+        resume_unwind(payload)
+    }
+
+    /// The start of a dispatched message, which is only recorded if message tracing is enabled.
+    struct Trace {
+        start: Instant,
+        params: Option<String>
+    }
+
+    fn start_trace(connection: &mut impl RpcConnection, params: &Value) -> Option<Trace> {
+        if !connection.trace_messages() { return None }
+        let params = match connection.trace_value() {
+            TraceValue::Off => return None,
+            TraceValue::Messages => None,
+            TraceValue::Verbose => Some(format!("Params: {params}"))
+        };
+
+        Some(Trace {
+            start: Instant::now(),
+            params
+        })
+    }
+
+    fn finish_trace(connection: &mut impl RpcConnection, trace: Option<Trace>, message: impl FnOnce() -> String) {
+        let Some(trace) = trace else { return };
+        connection.notify("$/logTrace", LogTraceParams {
+            message: format!("{} in {:.2?}.", message(), trace.start.elapsed()),
+            verbose: trace.params
+        });
+    }
+
+    fn handle_concurrent<R: RpcResolver>(server: &mut R, handler: ConcurrentHandler<R>, task: Task, params: Value) {
+        let job = match handler(server, params) {
+            Ok(job) => job,
+            Err(error) => return finish_task(server.connection(), task, Ok(Err(RpcError {
+                code: ErrorCode::InvalidParams,
+                message: format!("Failed to parse params: {error}"),
+            })))
+        };
+
+        let job = match server.connection().workers() {
+            Some(workers) => workers.spawn(task, job),
+            None => Some((task, job))
+        };
+
+        if let Some((task, job)) = job {
+            let result = catch_unwind(AssertUnwindSafe(job))
+                .unwrap_or_else(|payload| panicked(server.connection(), &task.method, task.size, task.start, payload));
+            finish_task(server.connection(), task, Ok(result));
+        }
+    }
+
+    /// Waits for all requests, which are still running on worker threads, and sends their responses.
+    fn finish_tasks(connection: &mut impl RpcConnection) {
+        while let Some((task, result)) = connection.workers().and_then(|workers| workers.recv()) {
+            finish_task(connection, task, result);
         }
+    }
 
-        match result {
-            Ok(result) => send(server.connection(), Message::Response {
+    fn finish_task(connection: &mut impl RpcConnection, task: Task, result: JobResult) {
+        let result = match result {
+            Ok(result) => result,
+            Err(message) => {
+                connection.record(&task.method, task.size, task.start.elapsed(), Outcome::Panic(&message));
+                return send_result(connection, task.id, Err(RpcError {
+                    code: ErrorCode::InternalError,
+                    message: "Request handler panicked".to_string()
+                }))
+            }
+        };
+
+        record(connection, &task.method, task.size, task.start, result.as_ref().map(|_| ()));
+        send_result(connection, task.id, result);
+    }
+
+    fn send_result(connection: &mut impl RpcConnection, id: MessageID, result: Result<Value, RpcError>) {
+        send(connection, match result {
+            Ok(result) => Message::Response {
                 jsonrpc: Version::Current,
                 id,
                 result
-            }),
-            Err(error) => send(server.connection(), Message::Error {
+            },
+            Err(error) => Message::Error {
                 jsonrpc: Version::Current,
                 id,
-                error: RpcError {
-                    code: ErrorCode::InvalidParams,
-                    message: format!("Failed to parse params: {error}"),
-                }
-            })
-        };
+                error
+            }
+        });
     }
 }
\ No newline at end of file
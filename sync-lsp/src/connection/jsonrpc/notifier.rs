@@ -0,0 +1,70 @@
+use std::sync::mpsc::{channel, Sender, Receiver};
+#[cfg(feature = "mio")]
+use std::sync::Arc;
+#[cfg(feature = "mio")]
+use mio::Waker;
+use log::error;
+use serde::Serialize;
+use serde_json::{Value, to_value};
+
+/// A sending handle for notifications, which may be used from any thread.
+/// The notifications are sent by the thread running the server, which is woken up if possible.
+#[derive(Clone)]
+pub(crate) struct Notifier {
+    sender: Sender<(&'static str, Value)>,
+    #[cfg(feature = "mio")]
+    waker: Option<Arc<Waker>>
+}
+
+/// The receiving end of all [`Notifier`]s, which is owned by the connection.
+pub(crate) struct Notifications {
+    sender: Sender<(&'static str, Value)>,
+    receiver: Receiver<(&'static str, Value)>
+}
+
+impl Notifier {
+    pub(crate) fn notify(&self, method: &'static str, params: impl Serialize) {
+        let params = match to_value(params) {
+            Ok(params) => params,
+            Err(error) => return error!("Failed to serialize params for {method} notification: {error}")
+        };
+
+        if self.sender.send((method, params)).is_ok() {
+            #[cfg(feature = "mio")]
+            if let Some(waker) = self.waker.as_ref() {
+                waker.wake().ok();
+            }
+        }
+    }
+}
+
+impl Notifications {
+    #[cfg(feature = "mio")]
+    pub(crate) fn notifier(&self, waker: Option<Arc<Waker>>) -> Notifier {
+        Notifier {
+            sender: self.sender.clone(),
+            waker
+        }
+    }
+
+    #[cfg(not(feature = "mio"))]
+    pub(crate) fn notifier(&self) -> Notifier {
+        Notifier {
+            sender: self.sender.clone()
+        }
+    }
+
+    pub(crate) fn try_recv(&self) -> Option<(&'static str, Value)> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Notifications {
+            sender,
+            receiver
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use serde_json::{Value, from_slice};
+use super::message::{Message, MessageID};
+
+/// The messages, which have been received but not handled yet, as far as they are relevant
+/// to a running handler. They are only parsed again once the messages buffered by the transport have changed.
+#[derive(Default)]
+pub(crate) struct MessageQueue {
+    generation: Option<u64>,
+    notifications: Vec<(String, Value)>,
+    responses: Vec<MessageID>
+}
+
+impl MessageQueue {
+    /// Returns the method and params of all queued notifications.
+    pub(crate) fn notifications(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.notifications.iter().map(|(method, params)| (method.as_str(), params))
+    }
+
+    /// Checks whether any response to a request with the given id prefix is queued.
+    pub(super) fn has_response(&self, prefix: &str) -> bool {
+        self.responses.iter().any(|id| matches!(id, MessageID::String(id) if id.starts_with(prefix)))
+    }
+
+    /// Checks whether the queue has been parsed from the current messages buffered by the transport.
+    pub(super) fn current(&self, generation: u64) -> bool {
+        self.generation == Some(generation)
+    }
+
+    /// Parses the messages buffered by the transport.
+    pub(crate) fn parse<'a>(generation: u64, buffered: impl Iterator<Item = &'a [u8]>) -> MessageQueue {
+        let mut queue = MessageQueue {
+            generation: Some(generation),
+            ..Default::default()
+        };
+
+        for data in buffered {
+            match from_slice(data) {
+                Ok(Message::Notification { method, params, .. }) => queue.notifications.push((method, params)),
+                Ok(Message::Response { id, .. } | Message::Error { id, .. }) => queue.responses.push(id),
+                _ => ()
+            }
+        }
+
+        queue
+    }
+}
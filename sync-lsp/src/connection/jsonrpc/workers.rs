@@ -0,0 +1,123 @@
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{channel, Sender, Receiver};
+use std::thread::Builder;
+use std::time::Instant;
+use log::error;
+use serde_json::Value;
+use super::message::{Error as RpcError, MessageID};
+
+/// A request handler which has been detached from the server and may be run on any thread.
+pub(crate) type Job = Box<dyn FnOnce() -> Result<Value, RpcError> + Send>;
+
+/// The result of a [`Job`] or the message of the panic it caused.
+pub(crate) type JobResult = Result<Result<Value, RpcError>, String>;
+
+/// A request, which has been handed to a worker.
+pub(crate) struct Task {
+    pub(crate) id: MessageID,
+    pub(crate) method: String,
+    pub(crate) size: usize,
+    pub(crate) start: Instant
+}
+
+/// A pool of threads running [`Job`]s. Results are collected in a queue,
+/// which is only ever drained by the thread running the server.
+/// Once the pool is dropped or resized, the threads stop after finishing all jobs queued before,
+/// whose results are still delivered through the same queue.
+pub(crate) struct Workers {
+    jobs: Sender<(Task, Job)>,
+    sender: Sender<(Task, JobResult)>,
+    results: Receiver<(Task, JobResult)>,
+    wake: Arc<dyn Fn() + Send + Sync>,
+    threads: usize,
+    pending: usize
+}
+
+impl Workers {
+    pub(crate) fn new(count: usize, wake: impl Fn() + Send + Sync + 'static) -> Workers {
+        let (sender, results) = channel();
+        let mut workers = Workers {
+            jobs: channel().0,
+            sender,
+            results,
+            wake: Arc::new(wake),
+            threads: 0,
+            pending: 0
+        };
+
+        workers.resize(count);
+        workers
+    }
+
+    /// Replaces the threads of this pool, while jobs queued before are finished by the previous threads.
+    pub(crate) fn resize(&mut self, count: usize) {
+        let (jobs, receiver) = channel::<(Task, Job)>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        self.jobs = jobs;
+
+        self.threads = (0..count).filter(|index| {
+            let receiver = receiver.clone();
+            let sender = self.sender.clone();
+            let wake = self.wake.clone();
+
+            let thread = Builder::new()
+                .name(format!("sync-lsp-worker-{index}"))
+                .spawn(move || loop {
+                    let Ok((task, job)) = receiver.lock()
+                        .map_err(|_| ())
+                        .and_then(|receiver| receiver.recv().map_err(|_| ())) else { break };
+
+                    let result = catch_unwind(AssertUnwindSafe(job))
+                        .map_err(|payload| panic_message(payload.as_ref()));
+
+                    if sender.send((task, result)).is_err() { break }
+                    wake();
+                });
+
+            thread.map_err(|error| error!("Failed to spawn worker thread: {error}")).is_ok()
+        }).count();
+    }
+
+    /// Queues a job or returns it, if no worker thread is available.
+    pub(crate) fn spawn(&mut self, task: Task, job: Job) -> Option<(Task, Job)> {
+        if self.threads == 0 {
+            return Some((task, job))
+        }
+
+        let job = self.jobs.send((task, job)).err().map(|error| error.0);
+        if job.is_none() {
+            self.pending += 1;
+        }
+        job
+    }
+
+    pub(crate) fn try_recv(&mut self) -> Option<(Task, JobResult)> {
+        let result = self.results.try_recv().ok()?;
+        self.pending -= 1;
+        Some(result)
+    }
+
+    /// Waits for the result of the next job, which is still running or queued, if there is any.
+    pub(crate) fn recv(&mut self) -> Option<(Task, JobResult)> {
+        if self.pending == 0 {
+            return None
+        }
+
+        let result = self.results.recv().ok()?;
+        self.pending -= 1;
+        Some(result)
+    }
+}
+
+/// Extracts the message of a panic payload, which is usually a string.
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Unknown panic payload".to_string()
+    }
+}
\ No newline at end of file
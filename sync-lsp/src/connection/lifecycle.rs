@@ -1,7 +1,7 @@
 use crate::TypeProvider;
 use crate::lifecycle::initialize::{InitializeParams, InitializeResult, ServerCapabilities};
 use crate::lifecycle::{LifecycleService, Initialized, Initialize, Shutdown, Exit, Cancel};
-use crate::text_document::TextDocumentSyncOptions;
+use crate::text_document::{TextDocumentSyncOptions, DocumentUri};
 use super::{ErrorCode, Server};
 use serde_json::from_value;
 use log::error;
@@ -13,7 +13,10 @@ impl<T: TypeProvider> Default for LifecycleService<T> {
             initialized: Initialized(initialized_error),
             shutdown: Shutdown(shutdown_error),
             exit: Exit(exit_error),
-            cancel: Cancel(|_| ())
+            cancel: Cancel(|_| ()),
+            on_shutdown: |_| (),
+            on_exit: |_| (),
+            exited: false
         }
     }
 }
@@ -23,6 +26,7 @@ fn initialize(server: &mut Server<impl TypeProvider>, params: InitializeParams)
     server.lifecycle.initialized = Initialized(initialized);
 
     if let Some(options) = params.initialization_options {
+        server.connection.telemetry.configure(&options);
         server.initialization_options = match from_value(options) {
             Ok(options) => Some(options),
             Err(error) => {
@@ -32,10 +36,25 @@ fn initialize(server: &mut Server<impl TypeProvider>, params: InitializeParams)
         };
     };
     
-    server.capabilities = params.capabilities;
+    let dynamic_sync = server.negotiate_document_sync(&params.capabilities);
+    server.connection.capabilities = params.capabilities;
     server.process_id = params.process_id;
-    server.root_uri = params.root_uri
-        .or(params.root_path);
+    server.connection.trace = params.trace.unwrap_or_default();
+    server.root_uri = params.root_uri.and_then(|uri| match DocumentUri::parse(&uri) {
+        Ok(uri) => Some(uri),
+        Err(error) => {
+            error!("Failed to parse root uri: {}", error);
+            None
+        }
+    });
+
+    if server.root_uri.is_none() {
+        server.root_uri = params.root_path.and_then(|path| {
+            let uri = DocumentUri::from_file_path(&path);
+            if uri.is_none() { error!("Failed to convert root path to a uri: {path}") }
+            uri
+        });
+    }
 
     InitializeResult {
         capabilities: ServerCapabilities {
@@ -45,7 +64,7 @@ fn initialize(server: &mut Server<impl TypeProvider>, params: InitializeParams)
                 will_save: server.text_document.will_save.static_registration(),
                 will_save_wait_until: server.text_document.will_save_wait_until.static_registration(),
                 save: server.text_document.did_save.options()
-            }),
+            }).filter(|_| !dynamic_sync),
             completion_provider: Some(server.text_document.completion.options())
                 .filter(|_| server.text_document.completion.static_registration()),
             execute_command_provider: Some(server.workspace.execute_command.options())
@@ -68,6 +87,8 @@ fn initialize(server: &mut Server<impl TypeProvider>, params: InitializeParams)
             document_formatting_provider: server.text_document.formatting.static_registration(),
             document_range_formatting_provider: server.text_document.range_formatting.static_registration(),
             rename_provider: server.text_document.rename.static_registration(),
+            diagnostic_provider: server.diagnostic_options(),
+            notebook_document_sync: server.notebook_document.options(),
         }
     }
 }
@@ -75,15 +96,19 @@ fn initialize(server: &mut Server<impl TypeProvider>, params: InitializeParams)
 fn initialized(server: &mut Server<impl TypeProvider>) {
     server.lifecycle.initialized = Initialized(initialized_error);
     server.lifecycle.shutdown = Shutdown(shutdown);
+    server.register_document_sync(false);
 }
 
 fn shutdown(server: &mut Server<impl TypeProvider>) {
     server.lifecycle.shutdown = Shutdown(shutdown_error);
     server.lifecycle.exit = Exit(exit);
+    (server.lifecycle.on_shutdown)(server);
 }
 
 fn exit(server: &mut Server<impl TypeProvider>) {
     server.lifecycle.exit = Exit(exit_error);
+    server.lifecycle.exited = true;
+    (server.lifecycle.on_exit)(server);
 }
 
 fn initialize_error(server: &mut Server<impl TypeProvider>, _: InitializeParams) -> InitializeResult {
@@ -108,8 +133,10 @@ fn shutdown_error(server: &mut Server<impl TypeProvider>) {
 }
 
 fn exit_error(server: &mut Server<impl TypeProvider>) {
+    server.lifecycle.exited = true;
+    (server.lifecycle.on_exit)(server);
     server.connection.error::<()>(
         ErrorCode::InvalidRequest,
-        "Only a shut down server may be exited".to_string()
+        "Exited without a prior shutdown request".to_string()
     );
 }
\ No newline at end of file
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use log::warn;
+use serde::Serialize;
+use serde_json::{Value, to_vec};
+use crate::{Server, TypeProvider};
+use super::{Callback, Outcome};
+
+/// Metrics about all requests and notifications handled by a server,
+/// which can be obtained via [`Server::metrics`].
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use sync_lsp::{Transport, TypeProvider, Server};
+///
+/// struct MyServerState;
+///
+/// #[sync_lsp::type_provider]
+/// impl TypeProvider for MyServerState {}
+///
+/// fn main() {
+///     let transport = Transport::stdio();
+///     let mut server = Server::new(MyServerState, transport);
+///     server.set_slow_handler_threshold(Some(Duration::from_millis(500)));
+///     server.set_metrics_request(true);
+///
+///     server.on_shutdown(|server| {
+///         for (method, metrics) in server.metrics().methods.iter() {
+///             log::info!("{method}: {} calls, {} errors", metrics.count, metrics.errors);
+///         }
+///     });
+///
+///     server.serve().unwrap();
+/// }
+/// ```
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct Metrics {
+    /// The metrics of each method, which has been called at least once.
+    pub methods: HashMap<String, MethodMetrics>
+}
+
+/// Metrics about a single method.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodMetrics {
+    /// The number of handled requests or notifications.
+    pub count: u64,
+    /// The number of handlers, which resulted in an error other than a cancellation.
+    pub errors: u64,
+    /// The number of cancelled requests.
+    pub cancelled: u64,
+    /// The number of handlers, which panicked.
+    pub panics: u64,
+    /// The total time spent in handlers in milliseconds.
+    pub total_milliseconds: f64,
+    /// The longest time spent in a single handler in milliseconds.
+    pub max_milliseconds: f64,
+    /// The number of handlers per latency bucket, where each bucket counts the handlers, which
+    /// took less than the corresponding bound in [`MethodMetrics::BUCKETS`] and the last one counts all others.
+    pub histogram: [u64; MethodMetrics::BUCKETS.len() + 1]
+}
+
+/// The settings of metrics, which are stored on the connection.
+#[derive(Default)]
+pub(crate) struct MetricsState {
+    pub(crate) metrics: Metrics,
+    slow_threshold: Option<Duration>,
+    request: bool
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Returns the metrics of all requests and notifications handled so far.
+    pub fn metrics(&self) -> &Metrics {
+        &self.connection.metrics.metrics
+    }
+
+    /// Sets a threshold, above which handlers are logged as slow along with their method and the size of their params.
+    /// This is disabled by default.
+    ///
+    /// # Argument
+    /// * `threshold` - The duration a handler may take or `None` to disable logging.
+    pub fn set_slow_handler_threshold(&mut self, threshold: Option<Duration>) {
+        self.connection.metrics.slow_threshold = threshold;
+    }
+
+    /// Sets whether the client may request the current [`Metrics`] via the custom `$/syncLsp/metrics` request.
+    /// This is disabled by default.
+    ///
+    /// # Argument
+    /// * `value` - If `true`, the request will be answered.
+    pub fn set_metrics_request(&mut self, value: bool) {
+        self.connection.metrics.request = value;
+    }
+
+    pub(super) fn resolve_metrics(&self, method: &str) -> Option<Callback<Server<T>>> {
+        if !self.connection.metrics.request || method != MetricsState::METHOD {
+            return None
+        }
+
+        Some(Callback::request(|server: &mut Server<T>, _: Value| {
+            server.connection.metrics.metrics.clone()
+        }))
+    }
+}
+
+impl MethodMetrics {
+    /// The upper bounds in milliseconds of all but the last bucket of [`MethodMetrics::histogram`].
+    pub const BUCKETS: [u64; 8] = [1, 5, 10, 50, 100, 500, 1000, 5000];
+}
+
+impl MetricsState {
+    const METHOD: &'static str = "$/syncLsp/metrics";
+
+    /// Returns the size of the serialized params in bytes, which is only measured if slow handlers are logged.
+    pub(crate) fn params_size(&self, params: &Value) -> usize {
+        match self.slow_threshold {
+            Some(_) => to_vec(params).map_or(0, |params| params.len()),
+            None => 0
+        }
+    }
+
+    pub(crate) fn record(&mut self, method: &str, size: usize, elapsed: Duration, outcome: &Outcome) {
+        if let Some(threshold) = self.slow_threshold.filter(|threshold| elapsed > *threshold) {
+            warn!("Slow handler for {method}: took {elapsed:.2?}, which exceeds {threshold:?} (params of {size} bytes)");
+        }
+
+        let metrics = match self.metrics.methods.get_mut(method) {
+            Some(metrics) => metrics,
+            None => self.metrics.methods.entry(method.to_string()).or_default()
+        };
+
+        let milliseconds = elapsed.as_secs_f64() * 1000.0;
+        let bucket = MethodMetrics::BUCKETS.iter()
+            .position(|bound| elapsed < Duration::from_millis(*bound))
+            .unwrap_or(MethodMetrics::BUCKETS.len());
+
+        metrics.count += 1;
+        metrics.total_milliseconds += milliseconds;
+        metrics.max_milliseconds = metrics.max_milliseconds.max(milliseconds);
+        metrics.histogram[bucket] += 1;
+
+        match outcome {
+            Outcome::Success => (),
+            Outcome::Cancelled => metrics.cancelled += 1,
+            Outcome::Error(..) => metrics.errors += 1,
+            Outcome::Panic(..) => metrics.panics += 1
+        }
+    }
+}
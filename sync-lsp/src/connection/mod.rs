@@ -1,26 +1,44 @@
 use std::io::Error;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+use serde::Deserialize;
 use jsonrpc::RpcError;
 
-pub use jsonrpc::ErrorCode;
+pub use jsonrpc::{ErrorCode, ResponseError, TraceValue, Middleware, Message, MessageID};
 pub use transport::Transport;
-pub(crate) use jsonrpc::{Callback, EmptyParams, RpcConnection, CancelParams};
+pub use event::EventSender;
+pub(crate) use jsonrpc::{Callback, EmptyParams, RpcConnection, CancelParams, Workers, Logging, Notifier, Notifications, Outcome, Middlewares, MessageQueue};
+pub use jsonrpc::ClientLogger;
 pub(crate) use rpc::Endpoint;
+pub use metrics::{Metrics, MethodMetrics};
 
 use crate::TypeProvider;
 use crate::lifecycle::LifecycleService;
 use crate::lifecycle::initialize::ClientCapabilities;
-use crate::text_document::TextDocumentService;
+use crate::text_document::{TextDocumentService, DocumentUri};
+use crate::text_document::documents::DocumentVersions;
+use crate::text_document::diagnostic_collection::DiagnosticCollection;
 use crate::window::WindowService;
+use crate::window::work_done_progress::{ProgressState, WorkDoneProgressCancel, WorkDoneProgressCancelParams};
+use crate::window::partial_result::RequestContext;
+use crate::window::telemetry::TelemetryState;
 use crate::workspace::WorkspaceService;
+use crate::notebook_document::NotebookDocumentService;
 
-use self::jsonrpc::{RpcConnectionImpl, MessageID};
+use self::event::UserEvents;
+use self::timer::Timers;
+use self::jsonrpc::RpcConnectionImpl;
+use self::metrics::MetricsState;
 
 mod rpc;
 mod jsonrpc;
 mod transport;
 mod lifecycle;
+mod event;
+mod timer;
+mod concurrent;
+mod metrics;
 
 /// This struct is a wrapper around the server state, which provides
 /// type via the [`TypeProvider`] trait. It also contains the connection
@@ -51,14 +69,15 @@ pub struct Server<T: TypeProvider> {
     pub connection: Connection<T>,
     state: T,
     process_id: Option<u32>,
-    root_uri: Option<String>,
+    root_uri: Option<DocumentUri>,
     initialization_options: Option<T::InitializeOptions>,
+    user_events: UserEvents<T>,
     
-    lifecycle: LifecycleService<T>,
+    pub(crate) lifecycle: LifecycleService<T>,
     pub(crate) window: WindowService<T>,
     pub(crate) text_document: TextDocumentService<T>,
+    pub(crate) notebook_document: NotebookDocumentService<T>,
     pub(crate) workspace: WorkspaceService<T>,
-    pub(crate) capabilities: ClientCapabilities,
 }
 
 /// This struct may be used to send notifications and requests to the client.
@@ -90,10 +109,39 @@ pub struct Server<T: TypeProvider> {
 pub struct Connection<T: TypeProvider> {
     transport: Transport,
     error: Option<RpcError>,
-    current_request: Option<MessageID>,
+    pub(crate) current_request: Option<MessageID>,
+    pub(crate) capabilities: ClientCapabilities,
+    pub(crate) logging: Logging,
+    notifications: Notifications,
+    middleware: Middlewares,
+    pub(crate) queue: MessageQueue,
+    pub(crate) progress: ProgressState,
+    pub(crate) context: RequestContext,
+    pub(crate) versions: DocumentVersions,
+    pub(crate) result_version: Option<(DocumentUri, i32)>,
+    pub(crate) request_document: Option<DocumentUri>,
+    pub(crate) diagnostics: DiagnosticCollection<T::DiagnosticData>,
+    pub(crate) telemetry: TelemetryState,
+    metrics: MetricsState,
+    timers: Timers<T>,
+    workers: Option<Workers>,
+    pub(crate) trace: TraceValue,
+    pub(crate) trace_messages: bool,
     marker: PhantomData<T>
 }
 
+/// The outcome of a single call to [`Server::process_next`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessStatus {
+    /// A message has been received and dispatched to its callback.
+    Processed,
+    /// No message arrived before the timeout elapsed.
+    TimedOut,
+    /// The client sent an exit notification or closed the connection.
+    /// All further calls will return this status without doing anything.
+    Stopped
+}
+
 impl<T: TypeProvider> Server<T> {
     /// Creates a new server with the given state and transport.
     pub fn new(state: T, transport: Transport) -> Server<T> {
@@ -103,22 +151,32 @@ impl<T: TypeProvider> Server<T> {
             process_id: None,
             root_uri: None,
             initialization_options: None,
+            user_events: Default::default(),
             lifecycle: Default::default(),
             window: Default::default(),
             text_document: Default::default(),
-            workspace: Default::default(),
-            capabilities: ClientCapabilities::default()
+            notebook_document: Default::default(),
+            workspace: Default::default()
         }
     }
 
+    /// Adds a [`Middleware`] to the end of the chain, which inspects and transforms all messages exchanged with the client.
+    ///
+    /// # Argument
+    /// * `middleware` - The middleware to add.
+    pub fn add_middleware(&mut self, middleware: impl Middleware) {
+        self.connection.middleware.push(middleware);
+    }
+
     /// Returns the process id of the server, if one is provided by the client.
     pub fn process_id(&self) -> Option<u32> {
         self.process_id
     }
 
     /// Returns the root uri of the workspace, if one is provided by the client.
-    pub fn root_uri(&self) -> Option<&str> {
-        self.root_uri.as_ref().map(|uri| uri.as_str())
+    /// If the client only provided a root path or the root uri is invalid, the root path is converted to a `file` uri.
+    pub fn root_uri(&self) -> Option<&DocumentUri> {
+        self.root_uri.as_ref()
     }
 
     /// Returns the initialization options as defined in [`TypeProvider::InitializeOptions`] if available and parsed correctly.
@@ -131,10 +189,51 @@ impl<T: TypeProvider> Server<T> {
         (&mut self.connection, &mut self.state)
     }
 
-    /// Starts the server. This will block the current thread.
-    /// Until there is either an error or the client sends a shutdown request.
-    pub fn serve(self) -> Result<(), Error> {
-        RpcConnectionImpl::serve(self)
+    /// Starts the server. This will block the current thread
+    /// until there is either an error, the client closes the connection or sends an exit notification.
+    /// Once the server stops, the state is returned, so that it may be persisted.
+    /// Cleanup work can also be done within [`Server::on_shutdown`] and [`Server::on_exit`].
+    pub fn serve(self) -> Result<T, Error> {
+        RpcConnectionImpl::serve(self).map(|server| server.state)
+    }
+
+    /// Handles at most one message from the client and returns control to the caller.
+    /// This can be used instead of [`Server::serve`] to embed the server into an existing event loop.
+    /// Logging and lifecycle messages are handled exactly like they would be in [`Server::serve`].
+    /// 
+    /// # Argument
+    /// * `timeout` - The maximum time to wait for a message, or `None` to wait indefinitely.
+    ///   Transports that can't be polled, like [`Transport::custom`] or any transport without the `mio` feature,
+    ///   will always block until a message arrives.
+    /// * `return` - The [`ProcessStatus`] or an io error which occured on the transport.
+    /// 
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use sync_lsp::{Transport, TypeProvider, Server, ProcessStatus};
+    /// 
+    /// struct MyServerState;
+    /// 
+    /// #[sync_lsp::type_provider]
+    /// impl TypeProvider for MyServerState {}
+    /// 
+    /// fn main() {
+    ///     let transport = Transport::stdio();
+    ///     let mut server = Server::new(MyServerState, transport);
+    /// 
+    ///     loop {
+    ///         match server.process_next(Some(Duration::from_millis(50))).unwrap() {
+    ///             ProcessStatus::Stopped => break,
+    ///             ProcessStatus::TimedOut => {
+    ///                 // React to events of the surrounding application here.
+    ///             },
+    ///             ProcessStatus::Processed => ()
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn process_next(&mut self, timeout: Option<Duration>) -> Result<ProcessStatus, Error> {
+        RpcConnectionImpl::process(self, timeout)
     }
 }
 
@@ -144,6 +243,23 @@ impl<T: TypeProvider> Connection<T> {
             transport,
             error: None,
             current_request: None,
+            capabilities: ClientCapabilities::default(),
+            logging: Default::default(),
+            notifications: Default::default(),
+            middleware: Default::default(),
+            queue: Default::default(),
+            progress: Default::default(),
+            context: Default::default(),
+            versions: Default::default(),
+            result_version: None,
+            request_document: None,
+            diagnostics: Default::default(),
+            telemetry: Default::default(),
+            metrics: Default::default(),
+            timers: Default::default(),
+            workers: None,
+            trace: TraceValue::Off,
+            trace_messages: false,
             marker: PhantomData
         }
     }
@@ -153,12 +269,18 @@ impl<T: TypeProvider> Connection<T> {
     /// returns a default value of the type `R`, which makes it possible
     /// to write
     /// ```
-    /// return self.error(ErrorCode::InvalidParams, "Test Error".to_string());
+    /// # use sync_lsp::{Connection, TypeProvider, ErrorCode};
+    /// # fn handler<T: TypeProvider>(connection: &mut Connection<T>) -> Vec<String> {
+    /// return connection.error(ErrorCode::InvalidParams, "Test Error".to_string());
+    /// # }
     /// ```
     /// instead of
     /// ```
-    /// self.error(ErrorCode::InvalidParams, "Test Error".to_string());
-    /// return SomeType::default();
+    /// # use sync_lsp::{Connection, TypeProvider, ErrorCode};
+    /// # fn handler<T: TypeProvider>(connection: &mut Connection<T>) -> Vec<String> {
+    /// connection.error::<()>(ErrorCode::InvalidParams, "Test Error".to_string());
+    /// return Vec::new();
+    /// # }
     /// ```
     pub fn error<R: Default>(&mut self, code: ErrorCode, message: String) -> R {
         self.error = Some(RpcError {
@@ -168,10 +290,13 @@ impl<T: TypeProvider> Connection<T> {
         R::default()
     }
 
-    /// Check whether the current request has been cancelled.
-    /// If this method has been called in a cancelled request,
+    /// Check whether the current request has been cancelled, either directly or
+    /// by cancelling a [progress](crate::window::work_done_progress) that has been started by it.
+    /// Pending updates of progresses are sent as well, including those held back until the client
+    /// confirmed the creation of the progress. If this method has been called in a cancelled request,
     /// a error with code [`ErrorCode::RequestCancelled`] will be returned to the client,
-    /// regardless of what the request handler returns.
+    /// regardless of what the request handler returns. Requests, which have been [invalidated](Connection::invalidated)
+    /// by a change to their document, are considered cancelled as well.
     /// 
     /// # Example
     /// ```
@@ -205,17 +330,39 @@ impl<T: TypeProvider> Connection<T> {
     /// }
     /// ```
     pub fn cancelled(&mut self) -> bool {
+        self.flush_notifications();
+        self.scan_queue();
+        self.take_created_progress();
+
         let Some(id) = self.current_request.clone() else { return false; };
-        while let Some(params) = self.peek_notification::<CancelParams>("$/cancelRequest") {
-            if params.id == id {
-                self.current_request = self.error(
-                    ErrorCode::RequestCancelled,
-                    "Request cancelled".to_string()
-                );
-                return true;
-            }
+        let mut cancelled = self.progress.cancelled(&id);
+        for (method, params) in self.queue.notifications() {
+            cancelled |= match method {
+                "$/cancelRequest" => CancelParams::deserialize(params)
+                    .is_ok_and(|params| params.id == id),
+                WorkDoneProgressCancel::METHOD => WorkDoneProgressCancelParams::deserialize(params).ok()
+                    .and_then(|params| self.progress.cancel(params))
+                    .is_some_and(|request| *request == id),
+                _ => false
+            };
+        }
+
+        if cancelled {
+            self.current_request = self.error(
+                ErrorCode::RequestCancelled,
+                "Request cancelled".to_string()
+            );
         }
-        false
+
+        cancelled || self.modified_by_queue()
+    }
+
+    /// Returns a sender for notifications, which may be used from other threads.
+    pub(crate) fn notifier(&mut self) -> Notifier {
+        #[cfg(feature = "mio")]
+        return self.notifications.notifier(self.transport.waker());
+        #[cfg(not(feature = "mio"))]
+        return self.notifications.notifier();
     }
 }
 
@@ -1,8 +1,13 @@
+use std::time::{Duration, Instant};
 use log::Level;
 
+use serde::Deserialize;
+use serde_json::Value;
 use crate::window::MessageType;
-use crate::{Connection, TypeProvider, Server};
-use super::jsonrpc::{RpcConnection, Callback, RpcError, MessageID, RpcResolver};
+use crate::window::partial_result::RequestContext;
+use crate::text_document::DocumentUri;
+use crate::{Connection, TypeProvider, Server, ErrorCode};
+use super::jsonrpc::{RpcConnection, Callback, RpcError, MessageID, RpcResolver, Workers, Logging, TraceValue, Notifications, Outcome, Middlewares, MessageQueue};
 
 pub(crate) struct Endpoint<T: TypeProvider, O: Clone + Default> {
     callback: Callback<Server<T>>,
@@ -21,7 +26,21 @@ impl<T: TypeProvider> RpcResolver for Server<T> {
         self.lifecycle.resolve(method)
             .or(self.window.resolve(method))
             .or(self.text_document.resolve(method))
+            .or(self.notebook_document.resolve(method))
             .or(self.workspace.resolve(method))
+            .or(self.resolve_metrics(method))
+    }
+
+    fn exited(&self) -> bool {
+        self.lifecycle.exited
+    }
+
+    fn dispatch_event(&mut self) -> bool {
+        self.dispatch_timer() || self.dispatch_user_event()
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.connection.timers.deadline()
     }
 }
 
@@ -47,8 +66,71 @@ impl<T: TypeProvider> RpcConnection for Connection<T> {
     }
 
     fn set_current_request(&mut self, id: Option<MessageID>) {
+        if id.is_none() {
+            self.context = RequestContext::default();
+        }
+
+        self.result_version = None;
+        self.request_document = None;
+
         self.current_request = id;
     }
+
+    fn validate_result(&mut self) {
+        let Some((uri, version)) = self.result_version.take() else { return };
+        if self.error.is_some() { return }
+
+        self.scan_queue();
+        if self.versions.modified(&uri, version, &self.queue) {
+            self.error::<()>(
+                ErrorCode::ContentModified,
+                format!("{uri} has been modified since version {version}")
+            );
+        }
+    }
+
+    fn set_request_context(&mut self, params: &Value) {
+        self.context = RequestContext::deserialize(params).unwrap_or_default();
+        self.request_document = params.pointer("/textDocument/uri")
+            .and_then(|uri| DocumentUri::deserialize(uri).ok());
+    }
+
+    fn logging(&mut self) -> &mut Logging {
+        &mut self.logging
+    }
+
+    fn workers(&mut self) -> Option<&mut Workers> {
+        self.workers.as_mut()
+    }
+
+    fn trace_value(&self) -> TraceValue {
+        self.trace
+    }
+
+    fn trace_messages(&self) -> bool {
+        self.trace_messages
+    }
+
+    fn notifications(&mut self) -> &mut Notifications {
+        &mut self.notifications
+    }
+
+    fn middleware(&mut self) -> &mut Middlewares {
+        &mut self.middleware
+    }
+
+    fn queue(&mut self) -> &mut MessageQueue {
+        &mut self.queue
+    }
+
+    fn params_size(&self, params: &Value) -> usize {
+        self.metrics.params_size(params)
+    }
+
+    fn record(&mut self, method: &str, size: usize, elapsed: Duration, outcome: Outcome) {
+        self.metrics.record(method, size, elapsed, &outcome);
+        self.record_telemetry(method, elapsed, &outcome);
+    }
 }
 
 impl<T: TypeProvider, O: Clone + Default> Endpoint<T, O> {
@@ -63,7 +145,7 @@ impl<T: TypeProvider, O: Clone + Default> Endpoint<T, O> {
         }
     }
 
-    pub(super) fn static_registration(&self) -> bool {
+    pub(crate) fn static_registration(&self) -> bool {
         self.static_registration
     }
 
@@ -76,6 +158,10 @@ impl<T: TypeProvider, O: Clone + Default> Endpoint<T, O> {
         self.callback = callback;
     }
 
+    pub(crate) fn set_static_registration(&mut self) {
+        self.static_registration = true;
+    }
+
     pub(crate) fn callback(&self) -> Callback<Server<T>> {
         self.callback.clone()
     }
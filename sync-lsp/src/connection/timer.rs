@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::{Connection, Server, TypeProvider};
+
+type Timer<T> = fn(&mut Server<T>, String);
+
+pub(crate) struct Timers<T: TypeProvider> {
+    pending: HashMap<String, (Instant, Timer<T>)>
+}
+
+impl<T: TypeProvider> Connection<T> {
+
+    /// Schedules a callback, which will be called on the server thread once the duration has elapsed.
+    /// Scheduling a callback with the key of a pending timer replaces it, which can be used to debounce work.
+    /// Timers are only run while the server is waiting for messages, which requires a transport that can be polled.
+    /// Otherwise they are run as soon as the next message from the client has been handled.
+    /// 
+    /// # Arguments
+    /// * `duration` - The time to wait before calling the callback.
+    /// * `key` - A key identifying the timer, for example the uri of a document.
+    /// * `callback` - A callback which is called with the following parameters once the timer has elapsed:
+    ///     * The server instance.
+    ///     * The key of the timer.
+    /// 
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use sync_lsp::{Transport, TypeProvider, Server};
+    /// 
+    /// struct MyServerState;
+    /// 
+    /// #[sync_lsp::type_provider]
+    /// impl TypeProvider for MyServerState {}
+    /// 
+    /// fn main() {
+    ///     let transport = Transport::stdio();
+    ///     let mut server = Server::new(MyServerState, transport);
+    /// 
+    ///     server.on_change(|server, document, _| {
+    ///         // Analyze the document once the user stopped typing for 300ms.
+    ///         server.connection.schedule_after(Duration::from_millis(300), document.uri.into(), |server, uri| {
+    ///             if let Ok(uri) = uri.parse() {
+    ///                 server.connection.publish_diagnostics(uri, None, Vec::new());
+    ///             }
+    ///         });
+    ///     });
+    /// 
+    ///     server.serve().unwrap();
+    /// }
+    /// ```
+    pub fn schedule_after(&mut self, duration: Duration, key: String, callback: fn(&mut Server<T>, String)) {
+        self.timers.pending.insert(key, (Instant::now() + duration, callback));
+    }
+
+    /// Cancels a timer that has been scheduled via [`Connection::schedule_after`].
+    /// 
+    /// # Argument
+    /// * `key` - The key of the timer.
+    /// * `return` - A boolean indicating whether a pending timer has been cancelled.
+    pub fn cancel_timer(&mut self, key: &str) -> bool {
+        self.timers.pending.remove(key).is_some()
+    }
+}
+
+impl<T: TypeProvider> Timers<T> {
+    pub(super) fn deadline(&self) -> Option<Instant> {
+        self.pending.values()
+            .map(|(deadline, _)| *deadline)
+            .min()
+    }
+
+    pub(super) fn take_elapsed(&mut self) -> Option<(String, Timer<T>)> {
+        let now = Instant::now();
+        let key = self.pending.iter()
+            .filter(|(_, (deadline, _))| *deadline <= now)
+            .min_by_key(|(_, (deadline, _))| *deadline)
+            .map(|(key, _)| key.clone())?;
+        let (_, callback) = self.pending.remove(&key)?;
+        Some((key, callback))
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+    pub(super) fn dispatch_timer(&mut self) -> bool {
+        let Some((key, callback)) = self.connection.timers.take_elapsed() else { return false };
+        callback(self, key);
+        true
+    }
+}
+
+impl<T: TypeProvider> Default for Timers<T> {
+    fn default() -> Self {
+        Timers {
+            pending: HashMap::new()
+        }
+    }
+}
@@ -8,7 +8,7 @@ use std::{io::{
     stdout, BufReader
 }, net::{ToSocketAddrs, TcpListener}};
 
-#[cfg(feature = "mio")]
+use std::collections::VecDeque;
 use std::time::Duration;
 
 #[cfg(feature = "mio")]
@@ -16,12 +16,17 @@ use mio::net::TcpStream;
 #[cfg(not(feature = "mio"))]
 use std::net::TcpStream;
 
+#[cfg(feature = "mio")]
+use std::sync::Arc;
+
 #[cfg(feature = "mio")]
 use mio::{
     Events,
     Poll,
+    Registry,
     Token,
-    Interest
+    Interest,
+    Waker
 };
 
 #[cfg(unix)]
@@ -39,6 +44,7 @@ use log::{
 /// tcp and custom transports are also supported.
 /// All errors that occur during sending and receiving will cause the
 /// [Server::serve](crate::Server::serve) method to immediately return with an error variant.
+/// If the client closes the input stream between two messages, the server stops without an error.
 pub struct Transport {
     raw: RawTransport,
     error: Option<Error>,
@@ -46,12 +52,25 @@ pub struct Transport {
     poll: Option<Poll>,
     #[cfg(feature = "mio")]
     events: Events,
-    buffer: Vec<Vec<u8>>
+    #[cfg(feature = "mio")]
+    waker: Option<Arc<Waker>>,
+    buffer: VecDeque<Vec<u8>>,
+    generation: u64
 }
 
+#[cfg(feature = "mio")]
+const INPUT: Token = Token(0);
+#[cfg(feature = "mio")]
+const WAKER: Token = Token(1);
+
+/// The capacity of the input buffer for stdio transports.
+/// It is larger than the internal buffer of stdin, so that reads bypass it
+/// and all pending data is visible through [`BufReader::buffer`].
+const STDIN_CAPACITY: usize = 1 << 16;
+
 enum RawTransport {
     Stdio {
-        input: StdinLock<'static>,
+        input: BufReader<StdinLock<'static>>,
         output: StdoutLock<'static>,
     },
     Tpc {
@@ -80,6 +99,35 @@ impl RawTransport {
             Self::Custom { output, .. } => output
         }
     }
+
+    fn buffered(&self) -> bool {
+        match self {
+            Self::Stdio { input, .. } => !input.buffer().is_empty(),
+            Self::Tpc { input, .. } => !input.buffer().is_empty(),
+            Self::Custom { .. } => false
+        }
+    }
+
+    #[cfg(feature = "mio")]
+    fn reregister(&mut self, registry: &Registry) -> Result<(), Error> {
+        match self {
+            #[cfg(unix)]
+            Self::Stdio { input, .. } => {
+                use std::os::fd::AsRawFd;
+                registry.reregister(
+                    &mut SourceFd(&input.get_ref().as_raw_fd()),
+                    INPUT,
+                    Interest::READABLE
+                )
+            },
+            Self::Tpc { input, .. } => registry.reregister(
+                input.get_mut(),
+                INPUT,
+                Interest::READABLE
+            ),
+            _ => Ok(())
+        }
+    }
 }
 
 impl Transport {
@@ -98,8 +146,11 @@ impl Transport {
             },
             error: None,
             #[cfg(feature = "mio")]
-            events: Events::with_capacity(1),
-            buffer: Vec::new(),
+            events: Events::with_capacity(2),
+            #[cfg(feature = "mio")]
+            waker: None,
+            buffer: VecDeque::new(),
+            generation: 0,
             #[cfg(feature = "mio")]
             poll: None
         }
@@ -122,7 +173,8 @@ impl Transport {
                 input
             },
             error: None,
-            buffer: Vec::new(),
+            buffer: VecDeque::new(),
+            generation: 0,
         })
     }
 
@@ -132,19 +184,18 @@ impl Transport {
     /// * `addr` - The address to connect to.
     #[cfg(feature = "mio")]
     pub fn tcp<T: ToSocketAddrs>(addr: T) -> Result<Transport, Error> {
-        let mut poll = Poll::new().ok();
         let listener = TcpListener::bind(addr)?;
         let (output, ..) = listener.accept()?;
         let input = output.try_clone()?;
         let mut input = TcpStream::from_std(input);
 
-        if let Some(poll) = poll.as_mut() {
+        let poll = Poll::new().ok().filter(|poll| {
             poll.registry().register(
                 &mut input,
-                Token(0),
+                INPUT,
                 Interest::READABLE
-            ).ok();
-        }
+            ).is_ok()
+        });
 
         let input = BufReader::new(input);
         let output = TcpStream::from_std(output);
@@ -155,8 +206,10 @@ impl Transport {
                 input
             },
             error: None,
-            events: Events::with_capacity(1),
-            buffer: Vec::new(),
+            events: Events::with_capacity(2),
+            waker: None,
+            buffer: VecDeque::new(),
+            generation: 0,
             poll
         })
     }
@@ -167,37 +220,43 @@ impl Transport {
         Transport {
             raw: RawTransport::Stdio {
                 output: stdout().lock(),
-                input: stdin().lock()
+                input: BufReader::with_capacity(STDIN_CAPACITY, stdin().lock())
             },
             error: None,
-            buffer: Vec::new(),
+            buffer: VecDeque::new(),
+            generation: 0,
         }
     }
 
     /// Locks the standard input and output streams and returns a transport.
     #[cfg(feature = "mio")]
     pub fn stdio() -> Transport {
-        let poll = Poll::new().ok();
         let input = stdin().lock();
 
+        // Stdin can only be polled if it is registered successfully,
+        // which for example isn't the case for regular files.
         #[cfg(unix)]
-        if let Some(poll) = poll.as_ref() {
+        let poll = Poll::new().ok().filter(|poll| {
             use std::os::fd::AsRawFd;
             poll.registry().register(
                 &mut SourceFd(&input.as_raw_fd()),
-                Token(0),
+                INPUT,
                 Interest::READABLE
-            ).ok();
-        }
+            ).is_ok()
+        });
+        #[cfg(not(unix))]
+        let poll = None;
         
         Transport {
             raw: RawTransport::Stdio {
                 output: stdout().lock(),
-                input
+                input: BufReader::with_capacity(STDIN_CAPACITY, input)
             },
             error: None,
-            events: Events::with_capacity(1),
-            buffer: Vec::new(),
+            events: Events::with_capacity(2),
+            waker: None,
+            buffer: VecDeque::new(),
+            generation: 0,
             poll
         }
     }
@@ -209,19 +268,20 @@ impl Transport {
     pub(crate) fn send(&mut self, message: String) {
         if self.error().is_some() { return }
         *self.error() = write!(self.raw.output(), "Content-Length: {}\r\n", message.len())
-            .or(write!(self.raw.output(), "Content-Type: {}\r\n", "application/vscode-jsonrpc; charset=utf-8"))
+            .or(write!(self.raw.output(), "Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n"))
             .or(write!(self.raw.output(), "\r\n{message}"))
             .or(self.raw.output().flush()).err();
     }
 
     pub(crate) fn recv(&mut self) -> Option<Vec<u8>> {
-        if let Some(data) = self.buffer.pop() {
+        if let Some(data) = self.buffer.pop_front() {
+            self.generation += 1;
             return Some(data)
         }
 
         if self.error().is_some() { return None }
         match self.try_recv() {
-            Ok(message) => Some(message),
+            Ok(message) => message,
             Err(error) => {
                 *self.error() = Some(error);
                 None
@@ -231,10 +291,14 @@ impl Transport {
 
 
     pub(crate) fn peek(&mut self) -> Option<Vec<u8>> {
-        if self.poll() && self.buffer.len() < 10192 {
-            let data = self.recv();
+        if self.ready(Duration::from_millis(1)) && self.buffer.len() < 10192 {
+            let data = self.try_recv().unwrap_or_else(|error| {
+                *self.error() = Some(error);
+                None
+            });
             if let Some(data) = data.clone() {
-                self.buffer.push(data)
+                self.buffer.push_back(data);
+                self.generation += 1;
             }
             data
         } else {
@@ -242,27 +306,85 @@ impl Transport {
         }
     }
 
+    /// Removes the next message, which has been peeked but not received yet.
+    pub(crate) fn discard_buffered(&mut self) {
+        self.buffer.pop_front();
+        self.generation += 1;
+    }
+
+    /// Removes and returns all messages, which have been peeked but not received yet and are accepted by the predicate.
+    pub(crate) fn take_buffered(&mut self, mut accept: impl FnMut(&[u8]) -> bool) -> Vec<Vec<u8>> {
+        let (taken, kept): (VecDeque<_>, _) = self.buffer.drain(..).partition(|data| accept(data));
+        self.buffer = kept;
+        self.generation += 1;
+        taken.into()
+    }
+
+    /// Returns a counter, which changes whenever messages are added to or removed from the buffer.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns all messages, which have been peeked but not received yet.
+    pub(crate) fn buffered(&self) -> impl Iterator<Item = &[u8]> {
+        self.buffer.iter().map(Vec::as_slice)
+    }
+
+    /// Waits until a message can be received, the timeout elapses or the waker is triggered.
+    /// If the transport can't be polled, this will always return true
+    /// and the next call to [`Transport::recv`] may block.
+    pub(crate) fn wait(&mut self, timeout: Option<Duration>) -> bool {
+        if !self.buffer.is_empty() || self.raw.buffered() { return true }
+        self.poll(timeout).unwrap_or(true)
+    }
+
+    /// Checks whether there is unread input, that hasn't been peeked yet.
+    fn ready(&mut self, timeout: Duration) -> bool {
+        if self.error().is_some() { return false }
+        self.raw.buffered() || self.poll(Some(timeout)).unwrap_or(false)
+    }
+
     #[cfg(feature = "mio")]
-    fn poll(&mut self) -> bool {
+    fn poll(&mut self, timeout: Option<Duration>) -> Option<bool> {
+        let poll = self.poll.as_mut()?;
+        // Mio is edge triggered, but reregistering the input
+        // reports data that is already pending again.
+        self.raw.reregister(poll.registry()).ok();
         self.events.clear();
-        if let Some(poll) = self.poll.as_mut() {
-            poll.poll(&mut self.events, Some(Duration::from_millis(1))).ok();
+        poll.poll(&mut self.events, timeout).ok();
+        Some(self.events.iter().any(|event| event.token() == INPUT))
+    }
+
+    /// Returns a waker, which interrupts [`Transport::wait`] and
+    /// is only available if the transport can be polled.
+    #[cfg(feature = "mio")]
+    pub(crate) fn waker(&mut self) -> Option<Arc<Waker>> {
+        if self.waker.is_none() {
+            let poll = self.poll.as_ref()?;
+            self.waker = Waker::new(poll.registry(), WAKER).ok().map(Arc::new);
         }
-        !self.events.is_empty()
+
+        self.waker.clone()
     }
 
     #[cfg(not(feature = "mio"))]
-    fn poll(&mut self) -> bool {
-        false
+    fn poll(&mut self, _: Option<Duration>) -> Option<bool> {
+        None
     }
 
-    fn try_recv(&mut self) -> Result<Vec<u8>, Error> {
+    fn try_recv(&mut self) -> Result<Option<Vec<u8>>, Error> {
         loop {
             let mut content_length: Option<usize> = None;
-    
-            for line in self.raw.input().lines() {
+            let mut line = String::new();
+
+            loop {
+                line.clear();
+                if self.raw.input().read_line(&mut line)? == 0 {
+                    // The client closed the stream between two messages.
+                    return Ok(None)
+                }
 
-                let line = line?;
+                let line = line.trim_end_matches(['\r', '\n']);
                 if line.is_empty() { break }
 
                 match line.split_once(": ") {
@@ -297,7 +419,7 @@ impl Transport {
 
             //eprintln!("Received: {message}", message = String::from_utf8_lossy(&buffer));
 
-            return Ok(buffer)
+            return Ok(Some(buffer))
         }
     }
 }
\ No newline at end of file
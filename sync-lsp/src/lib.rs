@@ -1,15 +1,17 @@
 #![doc = include_str!("../README.md")]
 
-pub use connection::{Transport, Connection, Server, ErrorCode};
+pub use connection::{Transport, Connection, Server, ErrorCode, ResponseError, ProcessStatus, EventSender, Middleware, Message, MessageID, Metrics, MethodMetrics};
 pub use sync_lsp_derive::type_provider;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use workspace::execute_command::Command;
+use window::telemetry::TelemetryEvent;
 use std::fmt::Debug;
 
 mod connection;
 mod lifecycle;
 pub mod text_document;
+pub mod notebook_document;
 pub mod window;
 pub mod workspace;
 
@@ -25,10 +27,14 @@ pub trait TypeProvider: 'static {
     type Command: Command;
     type CodeLensData: Serialize + DeserializeOwned;
     type CompletionData: Serialize + DeserializeOwned + Debug;
+    type DiagnosticData: Serialize + DeserializeOwned;
     type Configuration: DeserializeOwned;
     type InitializeOptions: DeserializeOwned;
     type ShowMessageRequestData: Serialize + DeserializeOwned + Default;
     type ApplyEditData: Serialize + DeserializeOwned + Default;
+    type ShowDocumentData: Serialize + DeserializeOwned + Default;
+    type TelemetryEvent: TelemetryEvent;
+    type UserEvent: Send + 'static;
 }
 
 //TODO
@@ -12,4 +12,17 @@ impl<T: TypeProvider> Exit<T> {
         let Exit(callback) = *self;
         Callback::notification(move |server, _: ()| callback(server))
     }
-}
\ No newline at end of file
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the callback that will be called right before the server stops, because the client sent `exit`.
+    /// After the callback returns, [`Server::serve`] returns the server state.
+    /// 
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as the exit notification is received:
+    ///     * The server instance receiving the notification.
+    pub fn on_exit(&mut self, callback: fn(&mut Server<T>)) {
+        self.lifecycle.on_exit = callback;
+    }
+}
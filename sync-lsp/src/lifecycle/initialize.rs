@@ -1,7 +1,7 @@
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
 use crate::{Server, TypeProvider};
-use crate::connection::Callback;
+use crate::connection::{Callback, TraceValue};
 use crate::text_document::{TextDocumentSyncOptions, TextDocumentClientCapabilities};
 use crate::text_document::code_lens::CodeLensOptions;
 use crate::text_document::completion::CompletionOptions;
@@ -9,6 +9,10 @@ use crate::text_document::document_link::DocumentLinkOptions;
 use crate::text_document::on_type_formatting::DocumentOnTypeFormattingOptions;
 use crate::text_document::signature_help::SignatureHelpOptions;
 use crate::workspace::execute_command::ExecuteCommandOptions;
+use crate::window::WindowClientCapabilities;
+use crate::workspace::WorkspaceClientCapabilities;
+use crate::text_document::diagnostic::DiagnosticOptions;
+use crate::notebook_document::NotebookDocumentSyncOptions;
 
 pub(crate) struct Initialize<T: TypeProvider>
     (pub(crate) fn(&mut Server<T>, params: InitializeParams) -> InitializeResult);
@@ -22,7 +26,7 @@ pub(crate) struct InitializeParams {
     pub(crate) initialization_options: Option<Value>,
     #[allow(unused)]
     pub(crate) capabilities: ClientCapabilities,
-    //pub(crate) trace: Option<TraceValue>,
+    pub(crate) trace: Option<TraceValue>,
 }
 
 #[derive(Serialize, Default)]
@@ -34,8 +38,9 @@ pub(crate) struct InitializeResult {
 #[derive(Deserialize, Default)]
 #[serde(rename_all = "camelCase", default)]
 pub(crate) struct ClientCapabilities {
-    //pub(crate) workspace: Option<WorkspaceClientCapabilities>,
+    pub(crate) workspace: WorkspaceClientCapabilities,
     pub(crate) text_document: TextDocumentClientCapabilities,
+    pub(crate) window: WindowClientCapabilities,
     //pub(crate) experimental: Option<Value>,
 }
 
@@ -66,6 +71,10 @@ pub(crate) struct ServerCapabilities {
     pub document_link_provider: Option<DocumentLinkOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub execute_command_provider: Option<ExecuteCommandOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostic_provider: Option<DiagnosticOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notebook_document_sync: Option<NotebookDocumentSyncOptions>,
 }
 #[derive(Deserialize, Debug, Default)]
 #[serde(rename_all = "camelCase", default)]
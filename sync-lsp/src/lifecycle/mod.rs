@@ -18,7 +18,10 @@ pub(super) struct LifecycleService<T: TypeProvider> {
     pub(super) initialized: Initialized<T>,
     pub(super) shutdown: Shutdown<T>,
     pub(super) exit: Exit<T>,
-    pub(super) cancel: Cancel<T>
+    pub(super) cancel: Cancel<T>,
+    pub(super) on_shutdown: fn(&mut Server<T>),
+    pub(super) on_exit: fn(&mut Server<T>),
+    pub(super) exited: bool
 }
 
 impl<T: TypeProvider> LifecycleService<T> {
@@ -32,4 +35,4 @@ impl<T: TypeProvider> LifecycleService<T> {
             _ => None
         }
     }
-}
\ No newline at end of file
+}
@@ -12,4 +12,20 @@ impl<T: TypeProvider> Shutdown<T> {
         let Shutdown(callback) = *self;
         Callback::request(move |server, _: ()| callback(server))
     }
-}
\ No newline at end of file
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the callback that will be called if the client asks the server to shut down.
+    /// This is the place to flush caches, stop background work or persist indexes,
+    /// as the client won't send any further requests except for `exit`.
+    /// Since shutdown is a request, the client waits for the callback to return,
+    /// and errors reported via [`Connection::error`](crate::Connection::error) are sent back as the response.
+    /// 
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as a shutdown is requested:
+    ///     * The server instance receiving the request.
+    pub fn on_shutdown(&mut self, callback: fn(&mut Server<T>)) {
+        self.lifecycle.on_shutdown = callback;
+    }
+}
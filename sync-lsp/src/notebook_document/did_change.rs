@@ -0,0 +1,80 @@
+//! implementation of the `notebookDocument/didChange` notification
+//!
+//! # Usage
+//! Whenever a notebook or the content of one of its cells is changed, [`Server::on_notebook_change`] is invoked.
+//! Changes to the text documents of cells are interpreted according to the
+//! [`TextDocumentSyncKind`](crate::text_document::TextDocumentSyncKind) set via [`Server::set_document_sync`].
+//! If the [document store](crate::text_document::documents) is enabled, the changes are applied to it beforehand.
+
+use crate::{Server, TypeProvider};
+use crate::connection::{Callback, Endpoint};
+use serde::Deserialize;
+use crate::text_document::DocumentUri;
+use super::{VersionedNotebookDocumentIdentifier, NotebookDocumentChangeEvent, open_cells, close_cells, push_cells};
+
+#[derive(Default, Clone)]
+pub(crate) struct DidChangeNotebookOptions;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidChangeNotebookDocumentParams {
+    notebook_document: VersionedNotebookDocumentIdentifier,
+    change: NotebookDocumentChangeEvent
+}
+
+impl DidChangeNotebookOptions {
+
+    pub(crate) const METHOD: &'static str = "notebookDocument/didChange";
+
+    pub(super) fn endpoint<T: TypeProvider>() -> Endpoint<T, DidChangeNotebookOptions> {
+        Endpoint::new(Callback::notification(|server: &mut Server<T>, params: DidChangeNotebookDocumentParams| {
+            let cells = DidChangeNotebookOptions::apply(server, &params);
+            push_cells(server, cells);
+        }))
+    }
+
+    /// Applies a change to the notebook and the text documents of its cells
+    /// and returns the uris of all cells, which have been opened or changed.
+    /// Changes to cells, which don't increase their version, are rejected.
+    fn apply<T: TypeProvider>(server: &mut Server<T>, params: &DidChangeNotebookDocumentParams) -> Vec<DocumentUri> {
+        server.notebook_document.notebooks.change(&params.notebook_document, &params.change);
+
+        let mut changed = Vec::new();
+        let Some(cells) = params.change.cells.as_ref() else { return changed };
+        if let Some(structure) = cells.structure.as_ref() {
+            close_cells(server, &structure.did_close);
+            open_cells(server, &structure.did_open);
+            changed.extend(structure.did_open.iter().map(|cell| cell.uri.clone()));
+        }
+
+        for change in cells.text_content.iter().flatten() {
+            let document = &change.document;
+            if server.connection.versions.change(&document.uri, document.version) {
+                server.text_document.documents.change(&document.uri, document.version, &change.changes, server.text_document.sync_kind);
+                if !changed.contains(&document.uri) {
+                    changed.push(document.uri.clone());
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the callback that will be called if a [notebook is changed](self).
+    ///
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as a notebook is changed:
+    ///     * The server instance receiving the notification.
+    ///     * The [`VersionedNotebookDocumentIdentifier`] of the notebook that changed.
+    ///     * The [`NotebookDocumentChangeEvent`] that contains the changes to the notebook and its cells.
+    pub fn on_notebook_change(&mut self, callback: fn(&mut Server<T>, VersionedNotebookDocumentIdentifier, NotebookDocumentChangeEvent)) {
+        self.notebook_document.did_change.set_callback(Callback::notification(move |server: &mut Server<T>, params: DidChangeNotebookDocumentParams| {
+            let cells = DidChangeNotebookOptions::apply(server, &params);
+            callback(server, params.notebook_document, params.change);
+            push_cells(server, cells);
+        }))
+    }
+}
@@ -0,0 +1,55 @@
+//! implementation of the `notebookDocument/didClose` notification
+//!
+//! # Usage
+//! Whenever a notebook is closed, [`Server::on_notebook_close`] is invoked.
+//! If the [document store](crate::text_document::documents) is enabled, the notebook and the text documents
+//! of its cells are removed from it beforehand.
+
+use crate::{Server, TypeProvider};
+use crate::connection::{Callback, Endpoint};
+use crate::text_document::TextDocumentIdentifer;
+use serde::Deserialize;
+use super::{NotebookDocumentIdentifier, close_cells};
+
+#[derive(Default, Clone)]
+pub(crate) struct DidCloseNotebookOptions;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidCloseNotebookDocumentParams {
+    notebook_document: NotebookDocumentIdentifier,
+    cell_text_documents: Vec<TextDocumentIdentifer>
+}
+
+impl DidCloseNotebookOptions {
+
+    pub(crate) const METHOD: &'static str = "notebookDocument/didClose";
+
+    pub(super) fn endpoint<T: TypeProvider>() -> Endpoint<T, DidCloseNotebookOptions> {
+        Endpoint::new(Callback::notification(|server: &mut Server<T>, params: DidCloseNotebookDocumentParams| {
+            DidCloseNotebookOptions::apply(server, &params);
+        }))
+    }
+
+    fn apply<T: TypeProvider>(server: &mut Server<T>, params: &DidCloseNotebookDocumentParams) {
+        server.notebook_document.notebooks.close(&params.notebook_document.uri);
+        close_cells(server, &params.cell_text_documents);
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the callback that will be called if a [notebook is closed](self).
+    ///
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as a notebook is closed:
+    ///     * The server instance receiving the notification.
+    ///     * The [`NotebookDocumentIdentifier`] of the notebook that has been closed.
+    ///     * The [`Vec<TextDocumentIdentifer>`] containing the text documents of its cells.
+    pub fn on_notebook_close(&mut self, callback: fn(&mut Server<T>, NotebookDocumentIdentifier, Vec<TextDocumentIdentifer>)) {
+        self.notebook_document.did_close.set_callback(Callback::notification(move |server: &mut Server<T>, params: DidCloseNotebookDocumentParams| {
+            DidCloseNotebookOptions::apply(server, &params);
+            callback(server, params.notebook_document, params.cell_text_documents)
+        }))
+    }
+}
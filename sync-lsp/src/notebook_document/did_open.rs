@@ -0,0 +1,60 @@
+//! implementation of the `notebookDocument/didOpen` notification
+//!
+//! # Usage
+//! Whenever a notebook is opened, [`Server::on_notebook_open`] is invoked.
+//! If the [document store](crate::text_document::documents) is enabled, the notebook and the text documents
+//! of its cells are added to it beforehand.
+
+use crate::{Server, TypeProvider};
+use crate::connection::{Callback, Endpoint};
+use crate::text_document::did_open::TextDocumentItem;
+use serde::Deserialize;
+use crate::text_document::DocumentUri;
+use super::{NotebookDocument, open_cells, push_cells};
+
+#[derive(Default, Clone)]
+pub(crate) struct DidOpenNotebookOptions;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidOpenNotebookDocumentParams {
+    notebook_document: NotebookDocument,
+    cell_text_documents: Vec<TextDocumentItem>
+}
+
+impl DidOpenNotebookOptions {
+
+    pub(crate) const METHOD: &'static str = "notebookDocument/didOpen";
+
+    pub(super) fn endpoint<T: TypeProvider>() -> Endpoint<T, DidOpenNotebookOptions> {
+        Endpoint::new(Callback::notification(|server: &mut Server<T>, params: DidOpenNotebookDocumentParams| {
+            let cells = DidOpenNotebookOptions::apply(server, &params);
+            push_cells(server, cells);
+        }))
+    }
+
+    /// Tracks the notebook and its cells and returns the uris of the cells.
+    fn apply<T: TypeProvider>(server: &mut Server<T>, params: &DidOpenNotebookDocumentParams) -> Vec<DocumentUri> {
+        server.notebook_document.notebooks.open(&params.notebook_document);
+        open_cells(server, &params.cell_text_documents);
+        params.cell_text_documents.iter().map(|cell| cell.uri.clone()).collect()
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the callback that will be called if a [notebook is opened](self).
+    ///
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as a notebook is opened:
+    ///     * The server instance receiving the notification.
+    ///     * The [`NotebookDocument`] that has been opened.
+    ///     * The [`Vec<TextDocumentItem>`] containing the text documents of its cells.
+    pub fn on_notebook_open(&mut self, callback: fn(&mut Server<T>, NotebookDocument, Vec<TextDocumentItem>)) {
+        self.notebook_document.did_open.set_callback(Callback::notification(move |server: &mut Server<T>, params: DidOpenNotebookDocumentParams| {
+            let cells = DidOpenNotebookOptions::apply(server, &params);
+            callback(server, params.notebook_document, params.cell_text_documents);
+            push_cells(server, cells);
+        }))
+    }
+}
@@ -0,0 +1,43 @@
+//! implementation of the `notebookDocument/didSave` notification
+//!
+//! # Usage
+//! Whenever a notebook is saved, [`Server::on_notebook_save`] is invoked.
+//! Setting this callback announces to the client, that saved notebooks should be reported.
+
+use crate::{Server, TypeProvider};
+use crate::connection::{Callback, Endpoint};
+use serde::Deserialize;
+use super::NotebookDocumentIdentifier;
+
+#[derive(Default, Clone)]
+pub(crate) struct DidSaveNotebookOptions;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DidSaveNotebookDocumentParams {
+    notebook_document: NotebookDocumentIdentifier
+}
+
+impl DidSaveNotebookOptions {
+
+    pub(crate) const METHOD: &'static str = "notebookDocument/didSave";
+
+    pub(super) fn endpoint<T: TypeProvider>() -> Endpoint<T, DidSaveNotebookOptions> {
+        Endpoint::new(Callback::notification(|_, _: DidSaveNotebookDocumentParams| ()))
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the callback that will be called if a [notebook is saved](self).
+    ///
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as a notebook is saved:
+    ///     * The server instance receiving the notification.
+    ///     * The [`NotebookDocumentIdentifier`] of the saved notebook.
+    pub fn on_notebook_save(&mut self, callback: fn(&mut Server<T>, NotebookDocumentIdentifier)) {
+        self.notebook_document.did_save.set_callback(Callback::notification(move |server, params: DidSaveNotebookDocumentParams| {
+            callback(server, params.notebook_document)
+        }))
+    }
+}
@@ -0,0 +1,276 @@
+//! A module containing all notebook document related functionality.
+//!
+//! Notebooks, like Jupyter notebooks, consist of cells, each of which has its own text document.
+//! The server announces notebook synchronization for all notebooks matching a selector set via
+//! [`Server::set_notebook_selector`]. If the [document store](crate::text_document::documents) is enabled,
+//! the text documents of all cells are tracked by it as well.
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use serde_repr::{Serialize_repr, Deserialize_repr};
+use crate::{Server, TypeProvider};
+use crate::connection::{Callback, Endpoint};
+use crate::text_document::{DocumentUri, TextDocumentIdentifer, VersionedTextDocumentIdentifier};
+use crate::text_document::did_open::TextDocumentItem;
+use crate::text_document::did_change::TextDocumentContentChangeEvent;
+use self::did_open::DidOpenNotebookOptions;
+use self::did_change::DidChangeNotebookOptions;
+use self::did_save::DidSaveNotebookOptions;
+use self::did_close::DidCloseNotebookOptions;
+use self::notebooks::NotebookDocuments;
+
+pub mod did_open;
+pub mod did_change;
+pub mod did_save;
+pub mod did_close;
+pub mod notebooks;
+
+/// This struct contains all notebook document endpoints, which are mainly used to resolve callbacks.
+pub(crate) struct NotebookDocumentService<T: TypeProvider> {
+    pub(super) selector: Vec<NotebookSelector>,
+    pub(super) notebooks: NotebookDocuments,
+    pub(super) did_open: Endpoint<T, DidOpenNotebookOptions>,
+    pub(super) did_change: Endpoint<T, DidChangeNotebookOptions>,
+    pub(super) did_save: Endpoint<T, DidSaveNotebookOptions>,
+    pub(super) did_close: Endpoint<T, DidCloseNotebookOptions>
+}
+
+/// A notebook, which consists of cells.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookDocument {
+    /// The uri of the notebook.
+    pub uri: DocumentUri,
+    /// The type of the notebook, like `jupyter-notebook`.
+    pub notebook_type: String,
+    /// The version of the notebook, which increases after each change, including changes to its cells.
+    pub version: i32,
+    /// Additional metadata stored with the notebook.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    /// The cells of the notebook.
+    pub cells: Vec<NotebookCell>
+}
+
+/// A cell of a notebook, whose content is stored in a separate text document.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookCell {
+    /// The kind of the cell.
+    pub kind: NotebookCellKind,
+    /// The uri of the text document containing the content of the cell.
+    pub document: DocumentUri,
+    /// Additional metadata stored with the cell.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    /// A summary of the last execution of the cell, if it has been executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_summary: Option<ExecutionSummary>
+}
+
+/// The kind of a [`NotebookCell`].
+#[repr(i32)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotebookCellKind {
+    /// A markup cell, which is usually formatted as markdown.
+    Markup = 1,
+    /// A code cell, which contains source code.
+    Code = 2
+}
+
+/// A summary of the last execution of a [`NotebookCell`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionSummary {
+    /// The strictly monotonically increasing execution order of the cell.
+    pub execution_order: u32,
+    /// Whether the execution was successful, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>
+}
+
+/// Identifies a notebook by its uri.
+#[derive(Deserialize, Debug, Clone)]
+pub struct NotebookDocumentIdentifier {
+    pub uri: DocumentUri
+}
+
+/// Identifies a specific version of a notebook.
+#[derive(Deserialize, Debug, Clone)]
+pub struct VersionedNotebookDocumentIdentifier {
+    pub uri: DocumentUri,
+    pub version: i32
+}
+
+/// A change to a notebook.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookDocumentChangeEvent {
+    /// The new metadata of the notebook, if it has changed.
+    pub metadata: Option<Value>,
+    /// Changes to the cells of the notebook.
+    pub cells: Option<NotebookDocumentCellChanges>
+}
+
+/// Changes to the cells of a notebook, which are applied in the order of the fields.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookDocumentCellChanges {
+    /// Cells, which have been added, removed or moved.
+    pub structure: Option<NotebookCellStructureChange>,
+    /// Cells, whose metadata or execution summary has changed.
+    pub data: Option<Vec<NotebookCell>>,
+    /// Changes to the text documents of cells.
+    pub text_content: Option<Vec<NotebookCellTextChange>>
+}
+
+/// A structural change to the cells of a notebook.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookCellStructureChange {
+    /// The change to the array of cells.
+    pub array: NotebookCellArrayChange,
+    /// The text documents of added cells.
+    #[serde(default)]
+    pub did_open: Vec<TextDocumentItem>,
+    /// The text documents of removed cells.
+    #[serde(default)]
+    pub did_close: Vec<TextDocumentIdentifer>
+}
+
+/// Replaces a range of cells with new cells.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookCellArrayChange {
+    /// The index of the first cell to delete.
+    pub start: u32,
+    /// The number of cells to delete.
+    pub delete_count: u32,
+    /// The cells to insert at the start index.
+    #[serde(default)]
+    pub cells: Vec<NotebookCell>
+}
+
+/// Changes to the text document of a cell.
+#[derive(Deserialize, Debug, Clone)]
+pub struct NotebookCellTextChange {
+    /// The text document of the cell and its new version.
+    pub document: VersionedTextDocumentIdentifier,
+    /// The changes to the text document.
+    pub changes: Vec<TextDocumentContentChangeEvent>
+}
+
+/// Selects notebooks and their cells for synchronization. At least one of both fields should be set.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct NotebookSelector {
+    /// The notebooks to synchronize. If omitted, all notebooks containing a matching cell are synchronized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notebook: Option<NotebookDocumentFilter>,
+    /// The languages of cells to synchronize. If omitted, all cells are synchronized.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cells: Option<Vec<NotebookCellLanguage>>
+}
+
+/// Restricts synchronization to notebooks with a specific type, scheme or path.
+/// All fields, which are set, have to match.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookDocumentFilter {
+    /// The type of the notebook, like `jupyter-notebook`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notebook_type: Option<String>,
+    /// A uri scheme, like `file` or `untitled`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+    /// A glob pattern, which is matched against the path of a notebook, like `**/*.ipynb`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>
+}
+
+/// The language of cells, which should be synchronized.
+#[derive(Serialize, Debug, Clone)]
+pub struct NotebookCellLanguage {
+    /// A language id, like `python`.
+    pub language: String
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NotebookDocumentSyncOptions {
+    notebook_selector: Vec<NotebookSelector>,
+    save: bool
+}
+
+impl<T: TypeProvider> NotebookDocumentService<T> {
+    pub(super) fn resolve(&self, method: &str) -> Option<Callback<Server<T>>> {
+        match method {
+            DidOpenNotebookOptions::METHOD => Some(self.did_open.callback()),
+            DidChangeNotebookOptions::METHOD => Some(self.did_change.callback()),
+            DidSaveNotebookOptions::METHOD => Some(self.did_save.callback()),
+            DidCloseNotebookOptions::METHOD => Some(self.did_close.callback()),
+            _ => None
+        }
+    }
+
+    /// Returns the capability announcing notebook synchronization, which requires a selector.
+    pub(crate) fn options(&self) -> Option<NotebookDocumentSyncOptions> {
+        if self.selector.is_empty() {
+            return None
+        }
+
+        Some(NotebookDocumentSyncOptions {
+            notebook_selector: self.selector.clone(),
+            save: self.did_save.static_registration()
+        })
+    }
+}
+
+impl<T: TypeProvider> Default for NotebookDocumentService<T> {
+    fn default() -> Self {
+        NotebookDocumentService {
+            selector: Vec::new(),
+            notebooks: NotebookDocuments::default(),
+            did_open: DidOpenNotebookOptions::endpoint(),
+            did_change: DidChangeNotebookOptions::endpoint(),
+            did_save: DidSaveNotebookOptions::endpoint(),
+            did_close: DidCloseNotebookOptions::endpoint()
+        }
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the notebooks, which should be synchronized with the server.
+    /// Notebook synchronization is only announced to the client, if the selector isn't empty.
+    ///
+    /// # Argument
+    /// * `selector` - The notebooks and cells, which should be synchronized.
+    pub fn set_notebook_selector(&mut self, selector: Vec<NotebookSelector>) {
+        self.notebook_document.selector = selector;
+    }
+}
+
+/// Tracks the text documents of cells, which have been opened, in the [document store](crate::text_document::documents).
+fn open_cells<T: TypeProvider>(server: &mut Server<T>, cells: &[TextDocumentItem]) {
+    for cell in cells {
+        server.connection.versions.open(&cell.uri, cell.version);
+        server.text_document.documents.open(cell);
+    }
+}
+
+/// Stops tracking the text documents of cells, which have been closed.
+fn close_cells<T: TypeProvider>(server: &mut Server<T>, cells: &[TextDocumentIdentifer]) {
+    for cell in cells {
+        server.connection.versions.close(&cell.uri);
+        server.text_document.documents.close(&cell.uri);
+        server.connection.close_diagnostics(&cell.uri);
+        server.clear_pushed_diagnostics(&cell.uri);
+    }
+}
+
+/// Computes and publishes diagnostics of cells, which have been opened or changed, if the client doesn't support pulling them.
+fn push_cells<T: TypeProvider>(server: &mut Server<T>, cells: Vec<DocumentUri>) {
+    for cell in cells {
+        server.push_diagnostics(&cell);
+    }
+}
@@ -0,0 +1,135 @@
+//! implementation of a built-in store for open notebooks.
+//!
+//! # Usage
+//! Once the [document store](crate::text_document::documents) is enabled via [`Server::set_document_store`],
+//! the server keeps track of all open notebooks and their cells. The notebooks can be accessed via [`Server::notebooks`],
+//! while the text documents of their cells are available via [`Server::documents`].
+//!
+//! # Example
+//! ```
+//! use sync_lsp::{Transport, TypeProvider, Server};
+//! use sync_lsp::notebook_document::{NotebookSelector, NotebookDocumentFilter};
+//!
+//! struct MyServerState;
+//!
+//! #[sync_lsp::type_provider]
+//! impl TypeProvider for MyServerState {}
+//!
+//! fn main() {
+//!     let transport = Transport::stdio();
+//!     let mut server = Server::new(MyServerState, transport);
+//!     server.set_document_store(true);
+//!     server.set_notebook_selector(vec![NotebookSelector {
+//!         notebook: Some(NotebookDocumentFilter {
+//!             notebook_type: Some("jupyter-notebook".to_string()),
+//!             ..Default::default()
+//!         }),
+//!         ..Default::default()
+//!     }]);
+//!
+//!     server.on_notebook_change(|server, notebook, _| {
+//!         let Some(notebook) = server.notebooks().get(&notebook.uri) else { return };
+//!         for cell in notebook.cells.iter() {
+//!             if let Some(document) = server.documents().get(&cell.document) {
+//!                 log::info!("{} has {} lines", cell.document, document.text().len_lines());
+//!             }
+//!         }
+//!     });
+//!
+//!     server.serve().unwrap();
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::collections::hash_map::Values;
+use log::error;
+use crate::{Server, TypeProvider};
+use crate::text_document::DocumentUri;
+use super::{NotebookDocument, VersionedNotebookDocumentIdentifier, NotebookDocumentChangeEvent};
+
+/// All notebooks, which are currently open on the client.
+#[derive(Default)]
+pub struct NotebookDocuments {
+    pub(crate) enabled: bool,
+    notebooks: HashMap<DocumentUri, NotebookDocument>
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Returns all open [notebooks](self), which is empty unless [`Server::set_document_store`] has been enabled.
+    pub fn notebooks(&self) -> &NotebookDocuments {
+        &self.notebook_document.notebooks
+    }
+}
+
+impl NotebookDocuments {
+
+    /// Returns the notebook with the given uri, if it is open.
+    pub fn get(&self, uri: &DocumentUri) -> Option<&NotebookDocument> {
+        self.notebooks.get(uri)
+    }
+
+    /// Returns the notebook containing the cell with the given text document, if it is open.
+    pub fn get_by_cell(&self, uri: &DocumentUri) -> Option<&NotebookDocument> {
+        self.notebooks.values().find(|notebook| notebook.cells.iter().any(|cell| cell.document == *uri))
+    }
+
+    /// Returns an iterator over all open notebooks.
+    pub fn iter(&self) -> Values<'_, DocumentUri, NotebookDocument> {
+        self.notebooks.values()
+    }
+
+    /// Returns the number of open notebooks.
+    pub fn len(&self) -> usize {
+        self.notebooks.len()
+    }
+
+    /// Returns whether no notebooks are open.
+    pub fn is_empty(&self) -> bool {
+        self.notebooks.is_empty()
+    }
+
+    pub(super) fn open(&mut self, notebook: &NotebookDocument) {
+        if !self.enabled { return }
+        self.notebooks.insert(notebook.uri.clone(), notebook.clone());
+    }
+
+    pub(super) fn change(&mut self, identifier: &VersionedNotebookDocumentIdentifier, change: &NotebookDocumentChangeEvent) {
+        if !self.enabled { return }
+        let Some(notebook) = self.notebooks.get_mut(&identifier.uri) else {
+            return error!("Received changes for {}, which is not open", identifier.uri);
+        };
+
+        notebook.version = identifier.version;
+        if let Some(metadata) = change.metadata.as_ref() {
+            notebook.metadata = Some(metadata.clone());
+        }
+
+        let Some(cells) = change.cells.as_ref() else { return };
+        if let Some(structure) = cells.structure.as_ref() {
+            let start = (structure.array.start as usize).min(notebook.cells.len());
+            let end = (start + structure.array.delete_count as usize).min(notebook.cells.len());
+            notebook.cells.splice(start..end, structure.array.cells.iter().cloned());
+        }
+
+        for data in cells.data.iter().flatten() {
+            if let Some(cell) = notebook.cells.iter_mut().find(|cell| cell.document == data.document) {
+                *cell = data.clone();
+            }
+        }
+    }
+
+    pub(super) fn close(&mut self, uri: &DocumentUri) {
+        if !self.enabled { return }
+        self.notebooks.remove(uri);
+    }
+}
+
+impl<'a> IntoIterator for &'a NotebookDocuments {
+    type Item = &'a NotebookDocument;
+    type IntoIter = Values<'a, DocumentUri, NotebookDocument>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
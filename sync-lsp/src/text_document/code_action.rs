@@ -19,17 +19,17 @@ pub(crate) struct CodeActionOptions;
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct CodeActionParams {
+struct CodeActionParams<D> {
     text_document: TextDocumentIdentifer,
     range: Range,
-    context: CodeActionContext
+    context: CodeActionContext<D>
 }
 
 /// Contains additional diagnostic information about the context in which a code action is run.
 #[derive(Deserialize, Debug)]
-pub struct CodeActionContext {
-    /// An array of diagnostics as defined by the server.
-    pub diagnostics: Vec<Diagnostic>
+pub struct CodeActionContext<D> {
+    /// An array of diagnostics as defined by the server, including their [data](Diagnostic::data).
+    pub diagnostics: Vec<Diagnostic<D>>
 }
 
 impl CodeActionOptions {
@@ -37,7 +37,7 @@ impl CodeActionOptions {
     pub(crate) const METHOD: &'static str = "textDocument/codeAction";
     
     pub(super) fn endpoint<T: TypeProvider>() -> Endpoint<T, CodeActionOptions> {
-        Endpoint::new(Callback::request(|_, _: CodeActionParams| Vec::<()>::new()))
+        Endpoint::new(Callback::request(|_, _: CodeActionParams<T::DiagnosticData>| Vec::<()>::new()))
     }
 }
 
@@ -52,11 +52,11 @@ impl<T: TypeProvider> Server<T> {
     ///     * The [`Range`] of the document for which code actions are requested.
     ///     * The [`CodeActionContext`] for which code actions are requested.
     ///     * `return` - A list of commands to execute.
-
-    pub fn on_code_action(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Range, CodeActionContext) -> Vec<T::Command>) {
-        self.text_document.code_action.set_callback(Callback::request(move |server, params: CodeActionParams| {
+    #[allow(clippy::type_complexity)]
+    pub fn on_code_action(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Range, CodeActionContext<T::DiagnosticData>) -> Vec<T::Command>) {
+        self.text_document.code_action.set_callback(Callback::request(move |server, params: CodeActionParams<T::DiagnosticData>| {
             callback(server, params.text_document, params.range, params.context).into_iter()
-                .map(|command| CommandContainer(command))
+                .map(CommandContainer)
                 .collect::<Vec<_>>()
         }))
     }
@@ -71,7 +71,7 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`TextDocumentIdentifer`] of the document for which code actions are requested.
     ///     * `return` - A list of code lenses to display.
-
+    #[allow(clippy::type_complexity)]
     pub fn on_code_lens(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer) -> Vec<CodeLens<T::Command, T::CodeLensData>>) {
         self.text_document.code_lens.set_callback(Callback::request(move |server, params: CodeLensParams| {
             callback(server, params.text_document)
@@ -85,7 +85,7 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`CodeLens`] to resolve with `command` set to `None`.
     ///    * `return` - The resolved code lens.
-
+    #[allow(clippy::type_complexity)]
     pub fn on_resolve_code_lens(&mut self, callback: fn(&mut Server<T>, CodeLens<T::Command, T::CodeLensData>) -> CodeLens<T::Command, T::CodeLensData>) {
         self.text_document.resolve_code_lens.set_callback(Callback::request(move |server, params| {
             callback(server, params)
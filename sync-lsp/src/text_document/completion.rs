@@ -170,8 +170,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`TextDocumentIdentifer`] of the document for which completions are requested.
     ///     * `return` - A list of completions to display.
-
-
     pub fn on_completion(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Position) -> CompletionList<T>) {
         self.text_document.completion.set_callback(Callback::request(move |server, params: TextDocumentPositionParams| {
             callback(server, params.text_document, params.position)
@@ -185,7 +183,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`CompletionItem`] to resolve.
     ///     * `return` - The resolved completion.
-
     pub fn on_resolve_completion(&mut self, callback: fn(&mut Server<T>, CompletionItem<T>) -> CompletionItem<T>) {
         self.text_document.resolve_completion.set_callback(Callback::request(move |server, item| {
             callback(server, item)
@@ -196,7 +193,6 @@ impl<T: TypeProvider> Server<T> {
     /// 
     /// # Argument
     /// * `trigger_characters` - A list of characters that trigger completion. 
-
     pub fn set_completion_trigger_character(&mut self, trigger_characters: Vec<String>) {
         self.text_document.completion.options_mut().trigger_characters = trigger_characters;
     }
@@ -205,9 +201,8 @@ impl<T: TypeProvider> Server<T> {
     /// 
     /// # Return
     /// * `true` if the client supports snippets, `false` otherwise.
-
     pub fn snippet_support(&self) -> bool {
-        self.capabilities.text_document.completion.completion_item.snippet_support
+        self.connection.capabilities.text_document.completion.completion_item.snippet_support
     }
 }
 
@@ -31,10 +31,27 @@ impl<T: TypeProvider> Server<T> {
     ///     * The [`TextDocumentIdentifer`] of the document for which a definition is requested.
     ///    * The [`Position`] at which a definition is requested.
     ///     * `return` - A list of [`Location`]s to display.
-
     pub fn on_definition(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Position) -> Vec<Location>) {
         self.text_document.definition.set_callback(Callback::request(move |server, params: TextDocumentPositionParams | {
             callback(server, params.text_document, params.position)
         }))
     }
+}
+
+impl<T: TypeProvider + Clone + Send> Server<T> {
+
+    /// Sets a callback to locate a [definition](self), which is run on a worker thread
+    /// if enabled via [`Server::set_worker_threads`].
+    /// 
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters to resolve a definition:
+    ///     * A snapshot of the server state.
+    ///     * The [`TextDocumentIdentifer`] of the document for which a definition is requested.
+    ///     * The [`Position`] at which a definition is requested.
+    ///     * `return` - A list of [`Location`]s to display.
+    pub fn on_definition_concurrent(&mut self, callback: fn(&T, TextDocumentIdentifer, Position) -> Vec<Location>) {
+        self.text_document.definition.set_callback(Server::concurrent(move |state, params: TextDocumentPositionParams| {
+            callback(state, params.text_document, params.position)
+        }))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,237 @@
+//! implementation of the `textDocument/diagnostic` request
+//!
+//! # Usage
+//! Clients supporting the pull model request diagnostics for a document via [`Server::on_diagnostic`].
+//! A result id may be attached to each report, which is passed back to the callback on the next request,
+//! so that the server can answer with [`DocumentDiagnosticReport::Unchanged`] if nothing has changed.
+//! The server may ask the client to pull diagnostics again via [`Server::refresh_diagnostics`].
+//!
+//! If the client doesn't support pulling diagnostics, the library falls back to the push model: the callback
+//! is invoked whenever a document, including the cell of a notebook, is opened or changed, as well as on [`Server::refresh_diagnostics`],
+//! and its reports are sent to the client via [`Connection::publish_diagnostics`](crate::Connection::publish_diagnostics).
+//!
+//! # Example
+//! ```
+//! use sync_lsp::{Transport, TypeProvider, Server};
+//! use sync_lsp::text_document::diagnostic::DocumentDiagnosticReport;
+//!
+//! struct MyServerState;
+//!
+//! #[sync_lsp::type_provider]
+//! impl TypeProvider for MyServerState {}
+//!
+//! fn main() {
+//!     let transport = Transport::stdio();
+//!     let mut server = Server::new(MyServerState, transport);
+//!
+//!     server.on_diagnostic(|server, document, previous_result_id| {
+//!         let version = server.connection.version(&document.uri).unwrap_or_default().to_string();
+//!         if previous_result_id.as_ref() == Some(&version) {
+//!             return DocumentDiagnosticReport::Unchanged { result_id: version }.into()
+//!         }
+//!
+//!         DocumentDiagnosticReport::Full {
+//!             result_id: Some(version),
+//!             items: Vec::new()
+//!         }.into()
+//!     });
+//!
+//!     server.serve().unwrap();
+//! }
+//! ```
+
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use crate::{Server, TypeProvider};
+use crate::connection::{Callback, Endpoint, RpcConnection};
+use crate::workspace::diagnostic::DiagnosticRefresh;
+use super::publish_diagnostics::Diagnostic;
+use super::{DocumentUri, TextDocumentIdentifer};
+
+#[derive(Serialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiagnosticOptions {
+    inter_file_dependencies: bool,
+    workspace_diagnostics: bool
+}
+
+type DiagnosticCallback<T> = fn(&mut Server<T>, TextDocumentIdentifer, Option<String>) -> RelatedDocumentDiagnosticReport<<T as TypeProvider>::DiagnosticData>;
+
+/// The state of the push model, which is used if the client doesn't support pulling diagnostics.
+pub(crate) struct DiagnosticFallback<T: TypeProvider> {
+    callback: Option<DiagnosticCallback<T>>,
+    result_ids: HashMap<DocumentUri, String>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DocumentDiagnosticParams {
+    text_document: TextDocumentIdentifer,
+    previous_result_id: Option<String>
+}
+
+/// A diagnostic report for a single document.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum DocumentDiagnosticReport<D> {
+    /// A full list of all diagnostics of the document.
+    Full {
+        /// An optional id, which is passed back to the server on the next request.
+        #[serde(rename = "resultId", skip_serializing_if = "Option::is_none")]
+        result_id: Option<String>,
+        /// All diagnostics of the document.
+        items: Vec<Diagnostic<D>>
+    },
+    /// Indicates that the diagnostics of the document haven't changed since the last request.
+    Unchanged {
+        /// The id of the previous report, which is still valid.
+        #[serde(rename = "resultId")]
+        result_id: String
+    }
+}
+
+/// A diagnostic report for a document, which may also contain reports for other documents,
+/// whose diagnostics depend on it.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedDocumentDiagnosticReport<D> {
+    /// The report of the requested document.
+    #[serde(flatten)]
+    pub report: DocumentDiagnosticReport<D>,
+    /// Reports of related documents, like header files.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub related_documents: HashMap<DocumentUri, DocumentDiagnosticReport<D>>
+}
+
+impl DiagnosticOptions {
+
+    pub(crate) const METHOD: &'static str = "textDocument/diagnostic";
+
+    pub(super) fn endpoint<T: TypeProvider>() -> Endpoint<T, DiagnosticOptions> {
+        Endpoint::new(Callback::request(|_, _: DocumentDiagnosticParams| {
+            RelatedDocumentDiagnosticReport::<T::DiagnosticData>::from(DocumentDiagnosticReport::Full {
+                result_id: None,
+                items: Vec::new()
+            })
+        }))
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the callback that will be called to [compute diagnostics](self) of a document.
+    ///
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as diagnostics are requested:
+    ///     * The server instance receiving the request.
+    ///     * The [`TextDocumentIdentifer`] of the document for which diagnostics are requested.
+    ///     * The result id of the last report for this document, if any.
+    ///     * `return` - The report of the document and optionally of related documents.
+    #[allow(clippy::type_complexity)]
+    pub fn on_diagnostic(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Option<String>) -> RelatedDocumentDiagnosticReport<T::DiagnosticData>) {
+        self.text_document.diagnostic_fallback.callback = Some(callback);
+        self.text_document.diagnostic.set_callback(Callback::request(move |server, params: DocumentDiagnosticParams| {
+            callback(server, params.text_document, params.previous_result_id)
+        }));
+    }
+
+    /// Sets whether the diagnostics of a document may change if another document changes,
+    /// in which case the client will also pull diagnostics of other open documents. This is disabled by default.
+    ///
+    /// # Argument
+    /// * `value` - If `true`, diagnostics may depend on other documents.
+    pub fn set_diagnostic_inter_file_dependencies(&mut self, value: bool) {
+        self.text_document.diagnostic.options_mut().inter_file_dependencies = value;
+    }
+
+    /// Asks the client to pull [diagnostics](self) of all documents again.
+    /// If the client doesn't support pulling diagnostics, they are computed and pushed for all open documents instead.
+    ///
+    /// # Argument
+    /// * `return` - A boolean indicating whether diagnostics will be refreshed.
+    pub fn refresh_diagnostics(&mut self) -> bool {
+        if self.connection.capabilities.text_document.diagnostic.is_some() {
+            return self.connection.capabilities.workspace.diagnostics.refresh_support
+                && self.connection.request(DiagnosticRefresh::METHOD, (), ())
+        }
+
+        if self.text_document.diagnostic_fallback.callback.is_none() {
+            return false
+        }
+
+        for uri in self.connection.versions.uris() {
+            self.push_diagnostics(&uri);
+        }
+
+        true
+    }
+
+    /// Returns the capability announcing pull diagnostics, if the client supports them.
+    pub(crate) fn diagnostic_options(&self) -> Option<DiagnosticOptions> {
+        if !self.text_document.diagnostic.static_registration() || self.connection.capabilities.text_document.diagnostic.is_none() {
+            return None
+        }
+
+        let mut options = self.text_document.diagnostic.options();
+        options.workspace_diagnostics = self.workspace.diagnostic.static_registration();
+        Some(options)
+    }
+
+    /// Computes and publishes diagnostics of a document, if the client doesn't support pulling them.
+    pub(crate) fn push_diagnostics(&mut self, uri: &DocumentUri) {
+        let Some(callback) = self.text_document.diagnostic_fallback.callback else { return };
+        if self.connection.capabilities.text_document.diagnostic.is_some() { return }
+
+        let previous_result_id = self.text_document.diagnostic_fallback.result_ids.get(uri).cloned();
+        let result = callback(self, TextDocumentIdentifer { uri: uri.clone() }, previous_result_id);
+
+        self.publish_report(uri.clone(), result.report);
+        for (uri, report) in result.related_documents {
+            self.publish_report(uri, report);
+        }
+    }
+
+    /// Clears pushed diagnostics of a closed document, if the client doesn't support pulling them.
+    pub(crate) fn clear_pushed_diagnostics(&mut self, uri: &DocumentUri) {
+        if self.text_document.diagnostic_fallback.callback.is_none() { return }
+        if self.connection.capabilities.text_document.diagnostic.is_some() { return }
+
+        self.text_document.diagnostic_fallback.result_ids.remove(uri);
+        self.connection.publish_diagnostics(uri.clone(), None, Vec::new());
+    }
+
+    fn publish_report(&mut self, uri: DocumentUri, report: DocumentDiagnosticReport<T::DiagnosticData>) {
+        let result_ids = &mut self.text_document.diagnostic_fallback.result_ids;
+        match report {
+            DocumentDiagnosticReport::Full { result_id, items } => {
+                match result_id {
+                    Some(result_id) => result_ids.insert(uri.clone(), result_id),
+                    None => result_ids.remove(&uri)
+                };
+                let version = self.connection.version(&uri);
+                self.connection.publish_diagnostics(uri, version, items);
+            },
+            DocumentDiagnosticReport::Unchanged { result_id } => {
+                result_ids.insert(uri, result_id);
+            }
+        }
+    }
+}
+
+impl<D> From<DocumentDiagnosticReport<D>> for RelatedDocumentDiagnosticReport<D> {
+    fn from(report: DocumentDiagnosticReport<D>) -> Self {
+        RelatedDocumentDiagnosticReport {
+            report,
+            related_documents: HashMap::new()
+        }
+    }
+}
+
+impl<T: TypeProvider> Default for DiagnosticFallback<T> {
+    fn default() -> Self {
+        DiagnosticFallback {
+            callback: None,
+            result_ids: HashMap::new()
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! implementation of a collection merging diagnostics of multiple sources.
+//!
+//! # Usage
+//! Servers running several independent analyses, like a parser and a linter, would overwrite each
+//! others diagnostics when calling [`Connection::publish_diagnostics`] for the same document.
+//! Instead, each analysis may store its diagnostics via [`Connection::set_diagnostics`] under its own source.
+//! The diagnostics of all sources are merged per document and only published, if they have actually changed.
+//! Diagnostics without a source are published with the source they have been stored under.
+//! If enabled via [`Server::set_clear_diagnostics_on_close`], the diagnostics of a document are cleared
+//! as soon as it is closed. Calling [`Connection::publish_diagnostics`] directly bypasses the collection.
+//!
+//! # Example
+//! ```
+//! use sync_lsp::{Transport, TypeProvider, Server};
+//! use sync_lsp::text_document::publish_diagnostics::Diagnostic;
+//!
+//! struct MyServerState;
+//!
+//! #[sync_lsp::type_provider]
+//! impl TypeProvider for MyServerState {}
+//!
+//! fn main() {
+//!     let transport = Transport::stdio();
+//!     let mut server = Server::new(MyServerState, transport);
+//!     server.set_clear_diagnostics_on_close(true);
+//!
+//!     server.on_change(|server, document, _| {
+//!         let parser = vec![Diagnostic { message: "unexpected token".to_string(), ..Default::default() }];
+//!         server.connection.set_diagnostics(document.uri.clone(), "parser", parser);
+//!         server.connection.set_diagnostics(document.uri, "linter", Vec::new());
+//!     });
+//!
+//!     server.serve().unwrap();
+//! }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use log::error;
+use serde_json::Value;
+use crate::{Connection, Server, TypeProvider};
+use crate::connection::RpcConnection;
+use super::publish_diagnostics::{Diagnostic, PublishDiagnostics, PublishDiagnosticsParams};
+use super::DocumentUri;
+
+/// The diagnostics of all documents, grouped by their source.
+pub struct DiagnosticCollection<D> {
+    documents: HashMap<DocumentUri, DocumentDiagnostics<D>>,
+    clear_on_close: bool
+}
+
+struct DocumentDiagnostics<D> {
+    sources: BTreeMap<String, Vec<Diagnostic<D>>>,
+    published: Option<Value>
+}
+
+impl<D> DiagnosticCollection<D> {
+
+    /// Returns the merged diagnostics of all sources for a document.
+    pub fn get<'a>(&'a self, uri: &DocumentUri) -> impl Iterator<Item = &'a Diagnostic<D>> {
+        self.documents.get(uri)
+            .into_iter()
+            .flat_map(|document| document.sources.values().flatten())
+    }
+
+    /// Returns the diagnostics of a single source for a document, if there are any.
+    pub fn source(&self, uri: &DocumentUri, source: &str) -> Option<&[Diagnostic<D>]> {
+        self.documents.get(uri)?.sources.get(source).map(Vec::as_slice)
+    }
+
+    /// Returns the uris of all documents with diagnostics in this collection.
+    pub fn uris(&self) -> impl Iterator<Item = &DocumentUri> {
+        self.documents.iter()
+            .filter(|(_, document)| !document.sources.is_empty())
+            .map(|(uri, _)| uri)
+    }
+}
+
+impl<T: TypeProvider> Connection<T> {
+
+    /// Returns the [collection](self) of diagnostics stored via [`Connection::set_diagnostics`].
+    pub fn diagnostics(&self) -> &DiagnosticCollection<T::DiagnosticData> {
+        &self.diagnostics
+    }
+
+    /// Replaces the diagnostics of a single source for a document and publishes
+    /// the [merged diagnostics](self) of all sources, if they have changed.
+    ///
+    /// # Arguments
+    /// * `uri` - The uri of the document.
+    /// * `source` - The source of the diagnostics, like `parser` or `linter`.
+    /// * `diagnostics` - All diagnostics of the source for this document, which may be empty to remove them.
+    pub fn set_diagnostics(&mut self, uri: DocumentUri, source: impl Into<String>, mut diagnostics: Vec<Diagnostic<T::DiagnosticData>>) {
+        let source = source.into();
+        for diagnostic in diagnostics.iter_mut() {
+            diagnostic.source.get_or_insert_with(|| source.clone());
+        }
+
+        let document = self.diagnostics.documents.entry(uri.clone()).or_insert_with(|| DocumentDiagnostics {
+            sources: BTreeMap::new(),
+            published: None
+        });
+
+        if diagnostics.is_empty() {
+            document.sources.remove(&source);
+        } else {
+            document.sources.insert(source, diagnostics);
+        }
+
+        self.sync_diagnostics(uri);
+    }
+
+    /// Removes the diagnostics of all sources for a document and clears them on the client.
+    ///
+    /// # Argument
+    /// * `uri` - The uri of the document.
+    pub fn clear_diagnostics(&mut self, uri: &DocumentUri) {
+        if let Some(document) = self.diagnostics.documents.get_mut(uri) {
+            document.sources.clear();
+            self.sync_diagnostics(uri.clone());
+        }
+    }
+
+    /// Removes the diagnostics of a single source for all documents and publishes the documents, which have changed.
+    ///
+    /// # Argument
+    /// * `source` - The source of the diagnostics to remove.
+    pub fn clear_diagnostic_source(&mut self, source: &str) {
+        let uris: Vec<DocumentUri> = self.diagnostics.documents.iter_mut()
+            .filter_map(|(uri, document)| document.sources.remove(source).map(|_| uri.clone()))
+            .collect();
+
+        for uri in uris {
+            self.sync_diagnostics(uri);
+        }
+    }
+
+    /// Clears the diagnostics of a closed document, if enabled via [`Server::set_clear_diagnostics_on_close`].
+    pub(crate) fn close_diagnostics(&mut self, uri: &DocumentUri) {
+        if self.diagnostics.clear_on_close {
+            self.clear_diagnostics(uri);
+        }
+    }
+
+    /// Publishes the merged diagnostics of a document, unless they equal the ones published last.
+    fn sync_diagnostics(&mut self, uri: DocumentUri) {
+        let Some(document) = self.diagnostics.documents.get_mut(&uri) else { return };
+
+        let merged: Vec<&Diagnostic<T::DiagnosticData>> = document.sources.values().flatten().collect();
+        let merged = match serde_json::to_value(merged) {
+            Ok(merged) => merged,
+            Err(err) => {
+                error!("Failed to serialize diagnostics for {uri}: {err}");
+                return
+            }
+        };
+
+        let empty = document.sources.is_empty();
+        let unchanged = document.published.as_ref() == Some(&merged) || (empty && document.published.is_none());
+        if empty {
+            self.diagnostics.documents.remove(&uri);
+        } else {
+            document.published = Some(merged.clone());
+        }
+
+        if unchanged {
+            return
+        }
+
+        let version = self.version(&uri);
+        self.notify(PublishDiagnostics::METHOD, PublishDiagnosticsParams {
+            uri,
+            version,
+            diagnostics: merged
+        });
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets whether the [collected diagnostics](self) of a document are cleared as soon as it is closed.
+    /// This is disabled by default.
+    ///
+    /// # Argument
+    /// * `value` - If `true`, diagnostics of all sources are removed and cleared on the client once a document is closed.
+    pub fn set_clear_diagnostics_on_close(&mut self, value: bool) {
+        self.connection.diagnostics.clear_on_close = value;
+    }
+}
+
+impl<D> Default for DiagnosticCollection<D> {
+    fn default() -> Self {
+        DiagnosticCollection {
+            documents: HashMap::new(),
+            clear_on_close: false
+        }
+    }
+}
@@ -4,18 +4,25 @@
 //! Whenever a document is changed, [`Server::on_change`] is invoked.
 //! The client should only send this if it claimed ownership of the document
 //! via [`Server::on_open`] before.
+//! Changes, which don't increase the [version](super::documents) of the document, are rejected.
+//! If the [document store](super::documents) is enabled, the changes are applied to it beforehand.
+//! Consecutive changes to the same document, which are already waiting to be processed,
+//! may be merged into a single invocation via [`Server::set_change_coalescing`].
 
 use crate::TypeProvider;
 use crate::{Server, connection::Endpoint};
-use crate::connection::Callback;
+use crate::connection::{Callback, RpcConnection};
 use serde::Deserialize;
+use serde_json::from_value;
 use super::{VersionedTextDocumentIdentifier, Range};
 
 #[derive(Default, Clone)]
-pub(crate) struct DidChangeOptions;
+pub(crate) struct DidChangeOptions {
+    coalesce: bool
+}
 
 /// A change to a text document.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentContentChangeEvent {
     /// If range is omitted, the new text is considered to be the full content of the document.
@@ -38,7 +45,67 @@ impl DidChangeOptions {
     pub(crate) const METHOD: &'static str = "textDocument/didChange";
     
     pub(super) fn endpoint<T: TypeProvider>() -> Endpoint<T, DidChangeOptions> {
-        Endpoint::new(Callback::notification(|_, _: DidChangeTextDocumentParams| ()))
+        Endpoint::new(Callback::notification(|server: &mut Server<T>, params: DidChangeTextDocumentParams| {
+            let params = DidChangeOptions::coalesce(server, params);
+            if DidChangeOptions::apply(server, &params) {
+                server.push_diagnostics(&params.text_document.uri);
+            }
+        }))
+    }
+
+    /// Merges all changes to the same document, which directly follow this one in the queue, if coalescing is enabled.
+    /// Changes, which don't increase the version or can't be parsed, are left in the queue, so that they are rejected individually.
+    fn coalesce<T: TypeProvider>(server: &mut Server<T>, mut params: DidChangeTextDocumentParams) -> DidChangeTextDocumentParams {
+        if !server.text_document.did_change.options().coalesce {
+            return params
+        }
+
+        while let Some(next) = server.connection.take_notification(|method, next| {
+            if method != DidChangeOptions::METHOD { return None }
+            from_value::<DidChangeTextDocumentParams>(next.clone()).ok()
+                .filter(|next| params.mergeable(next))
+        }) {
+            params.merge(next);
+        }
+
+        params
+    }
+
+    /// Tracks the version of a change and applies it to the document store,
+    /// which returns `false` if the change has been rejected or ignored by the [selector](super::selector).
+    fn apply<T: TypeProvider>(server: &mut Server<T>, params: &DidChangeTextDocumentParams) -> bool {
+        let document = &params.text_document;
+        if server.text_document.selector.ignores(&document.uri) {
+            return false
+        }
+
+        if !server.connection.versions.change(&document.uri, document.version) {
+            return false
+        }
+
+        server.text_document.documents.change(&document.uri, document.version, &params.content_changes, server.text_document.sync_kind);
+        true
+    }
+}
+
+impl DidChangeTextDocumentParams {
+
+    /// Checks whether a following change can be merged into this one,
+    /// which requires it to change the same document to a newer version.
+    fn mergeable(&self, next: &DidChangeTextDocumentParams) -> bool {
+        next.text_document.uri == self.text_document.uri && next.text_document.version > self.text_document.version
+    }
+
+    /// Appends the changes of a following notification, where a change containing
+    /// the full text replaces all changes before it.
+    fn merge(&mut self, next: DidChangeTextDocumentParams) {
+        self.text_document.version = next.text_document.version;
+        for change in next.content_changes {
+            if change.range.is_none() {
+                self.content_changes.clear();
+            }
+            self.content_changes.push(change);
+        }
     }
 }
 
@@ -51,10 +118,82 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`VersionedTextDocumentIdentifier`] of the document that changed.
     ///     * The [`Vec<TextDocumentContentChangeEvent>`] that contains the changes to the document.
-    
     pub fn on_change(&mut self, callback: fn(&mut Server<T>, VersionedTextDocumentIdentifier, Vec<TextDocumentContentChangeEvent>)) {
-        self.text_document.did_change.set_callback(Callback::notification(move |server, params: DidChangeTextDocumentParams| {
-            callback(server, params.text_document, params.content_changes)
+        self.text_document.did_change.set_callback(Callback::notification(move |server: &mut Server<T>, params: DidChangeTextDocumentParams| {
+            let params = DidChangeOptions::coalesce(server, params);
+            if DidChangeOptions::apply(server, &params) {
+                let uri = params.text_document.uri.clone();
+                callback(server, params.text_document, params.content_changes);
+                server.push_diagnostics(&uri);
+            }
         }));
     }
-}
\ No newline at end of file
+
+    /// Sets whether consecutive [changes](self) to the same document, which are waiting to be processed,
+    /// should be merged into a single invocation of [`Server::on_change`].
+    /// This is disabled by default and has no effect while middleware is installed.
+    /// Merged notifications still appear in message traces and [metrics](crate::Metrics).
+    ///
+    /// # Argument
+    /// * `value` - If `true`, incremental changes are concatenated in order, while changes
+    ///   containing the full text replace all changes before them.
+    pub fn set_change_coalescing(&mut self, value: bool) {
+        self.text_document.did_change.options_mut().coalesce = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_value, json, Value};
+    use super::DidChangeTextDocumentParams;
+
+    fn params(uri: &str, version: i32, changes: Value) -> DidChangeTextDocumentParams {
+        from_value(json!({
+            "textDocument": { "uri": uri, "version": version },
+            "contentChanges": changes
+        })).unwrap()
+    }
+
+    fn ranged(line: i32, text: &str) -> Value {
+        json!({
+            "range": { "start": { "line": line, "character": 0 }, "end": { "line": line, "character": 1 } },
+            "text": text
+        })
+    }
+
+    fn texts(params: &DidChangeTextDocumentParams) -> Vec<(bool, &str)> {
+        params.content_changes.iter().map(|change| (change.range.is_some(), change.text.as_str())).collect()
+    }
+
+    #[test]
+    fn merges_newer_changes_to_the_same_document() {
+        let current = params("file:///a.rs", 2, json!([]));
+        assert!(current.mergeable(&params("file:///a.rs", 3, json!([]))));
+        assert!(current.mergeable(&params("FILE:///a%2Ers", 5, json!([]))));
+        assert!(!current.mergeable(&params("file:///a.rs", 2, json!([]))));
+        assert!(!current.mergeable(&params("file:///a.rs", 1, json!([]))));
+        assert!(!current.mergeable(&params("file:///b.rs", 3, json!([]))));
+    }
+
+    #[test]
+    fn concatenates_ranged_changes() {
+        let mut current = params("file:///a.rs", 1, json!([ranged(0, "a")]));
+        current.merge(params("file:///a.rs", 2, json!([ranged(1, "b"), ranged(2, "c")])));
+        assert_eq!(current.text_document.version, 2);
+        assert_eq!(texts(&current), [(true, "a"), (true, "b"), (true, "c")]);
+    }
+
+    #[test]
+    fn replaces_changes_before_the_full_text() {
+        let mut current = params("file:///a.rs", 1, json!([ranged(0, "a")]));
+        current.merge(params("file:///a.rs", 2, json!([ranged(1, "b"), { "text": "full" }, ranged(0, "c")])));
+        assert_eq!(texts(&current), [(false, "full"), (true, "c")]);
+
+        current.merge(params("file:///a.rs", 4, json!([{ "text": "replaced" }])));
+        assert_eq!(current.text_document.version, 4);
+        assert_eq!(texts(&current), [(false, "replaced")]);
+
+        current.merge(params("file:///a.rs", 5, json!([ranged(0, "d")])));
+        assert_eq!(texts(&current), [(false, "replaced"), (true, "d")]);
+    }
+}
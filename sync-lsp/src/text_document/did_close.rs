@@ -4,11 +4,12 @@
 //! Whenever a document is closed, [`Server::on_close`] is invoked.
 //! The client should only send this if it claimed ownership of the document
 //! via [`Server::on_open`] before.
+//! If the [document store](super::documents) is enabled, the document is removed from it beforehand.
 
 use crate::{Server, TypeProvider};
 use crate::connection::{Callback, Endpoint};
 use serde::Deserialize;
-use super::TextDocumentIdentifer;
+use super::{TextDocumentIdentifer, DocumentUri};
 
 #[derive(Default, Clone)]
 pub(crate) struct DidCloseOptions;
@@ -25,10 +26,25 @@ impl DidCloseOptions {
     pub(crate) const METHOD: &'static str = "textDocument/didClose";
 
     pub(crate) fn endpoint<T: TypeProvider>() -> Endpoint<T, DidCloseOptions> {
-        Endpoint::new(Callback::notification(|_, _: DidCloseTextDocumentParams| {
-
+        Endpoint::new(Callback::notification(|server: &mut Server<T>, params: DidCloseTextDocumentParams| {
+            if DidCloseOptions::apply(server, &params.text_document.uri) {
+                server.clear_pushed_diagnostics(&params.text_document.uri);
+                server.connection.close_diagnostics(&params.text_document.uri);
+            }
         }))
     }
+
+    /// Stops tracking a closed document and removes it from the document store,
+    /// which returns `false` if it has been ignored by the [selector](super::selector).
+    fn apply<T: TypeProvider>(server: &mut Server<T>, uri: &DocumentUri) -> bool {
+        if !server.text_document.selector.close(uri) {
+            return false
+        }
+
+        server.connection.versions.close(uri);
+        server.text_document.documents.close(uri);
+        true
+    }
 }
 
 impl<T: TypeProvider> Server<T> {
@@ -39,10 +55,14 @@ impl<T: TypeProvider> Server<T> {
     /// * `callback` - A callback which is called with the following parameters as soon as a file is closed:
     ///     * The server instance receiving the response.
     ///     * The [`TextDocumentIdentifer`] of the document that has been closed.
-    
     pub fn on_close(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer)) {
-        self.text_document.did_close.set_callback(Callback::notification(move |server, params: DidCloseTextDocumentParams| {
-            callback(server, params.text_document)
+        self.text_document.did_close.set_callback(Callback::notification(move |server: &mut Server<T>, params: DidCloseTextDocumentParams| {
+            if DidCloseOptions::apply(server, &params.text_document.uri) {
+                let uri = params.text_document.uri.clone();
+                callback(server, params.text_document);
+                server.clear_pushed_diagnostics(&uri);
+                server.connection.close_diagnostics(&uri);
+            }
         }))
     }
 }
\ No newline at end of file
@@ -2,6 +2,7 @@
 //! 
 //! # Usage
 //! Whenever a document is opened, [`Server::on_open`] is invoked.
+//! If the [document store](super::documents) is enabled, the document is added to it beforehand.
 
 use crate::{Server, TypeProvider};
 use crate::connection::{Callback, Endpoint};
@@ -12,7 +13,7 @@ use super::DocumentUri;
 pub(crate) struct DidOpenOptions;
 
 /// A document that has been opened.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TextDocumentItem {
     /// The text document's URI.
@@ -36,7 +37,23 @@ impl DidOpenOptions {
     pub(crate) const METHOD: &'static str = "textDocument/didOpen";
     
     pub(super) fn endpoint<T: TypeProvider>() -> Endpoint<T, DidOpenOptions> {
-        Endpoint::new(Callback::notification(|_, _: DidOpenParams| ()))
+        Endpoint::new(Callback::notification(|server: &mut Server<T>, params: DidOpenParams| {
+            if DidOpenOptions::apply(server, &params.text_document) {
+                server.push_diagnostics(&params.text_document.uri);
+            }
+        }))
+    }
+
+    /// Tracks an opened document and adds it to the document store,
+    /// which returns `false` if it doesn't match the [selector](super::selector).
+    fn apply<T: TypeProvider>(server: &mut Server<T>, document: &TextDocumentItem) -> bool {
+        if !server.text_document.selector.open(document) {
+            return false
+        }
+
+        server.connection.versions.open(&document.uri, document.version);
+        server.text_document.documents.open(document);
+        true
     }
 }
 
@@ -49,10 +66,13 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`TextDocumentItem`] of the document that has been opened.
     ///
-
     pub fn on_open(&mut self, callback: fn(&mut Server<T>, TextDocumentItem)) {
-        self.text_document.did_open.set_callback(Callback::notification(move |server, params: DidOpenParams| {
-            callback(server, params.text_document)
+        self.text_document.did_open.set_callback(Callback::notification(move |server: &mut Server<T>, params: DidOpenParams| {
+            if DidOpenOptions::apply(server, &params.text_document) {
+                let uri = params.text_document.uri.clone();
+                callback(server, params.text_document);
+                server.push_diagnostics(&uri);
+            }
         }))
     }
 }
\ No newline at end of file
@@ -15,7 +15,7 @@ use serde::Serialize;
 #[derive(Serialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct DidSaveOptions {
-    include_text: bool
+    pub(super) include_text: bool
 }
 
 #[derive(Deserialize)]
@@ -44,9 +44,9 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`TextDocumentIdentifer`] of the saved document.
     ///     * The content of the file, if enabled via [`Server::set_save_include_text`].
-
     pub fn on_save(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Option<String>)) {
-        self.text_document.did_save.set_callback(Callback::notification(move |server, params: DidSaveTextDocumentParams| {
+        self.text_document.did_save.set_callback(Callback::notification(move |server: &mut Server<T>, params: DidSaveTextDocumentParams| {
+            if server.text_document.selector.ignores(&params.text_document.uri) { return }
             callback(server, params.text_document, params.text)
         }))
     }
@@ -55,7 +55,6 @@ impl<T: TypeProvider> Server<T> {
     /// 
     /// # Argument
     /// * `value` - If `true`, the content of the file will be included in the callback.
-
     pub fn set_save_include_text(&mut self, value: bool) {
         self.text_document.did_save.options_mut().include_text = value;
     }
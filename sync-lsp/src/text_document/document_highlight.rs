@@ -56,7 +56,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * The [`TextDocumentIdentifer`] of the target document.
     ///     * The [`Position`] of the cursor.
     ///     * `return` - A list of highlights to display.
-
     pub fn on_document_highlight(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Position) -> Vec<DocumentHighlight>) {
         self.text_document.document_highlight.set_callback(Callback::request(move |server, params: TextDocumentPositionParams| {
             callback(server, params.text_document, params.position)
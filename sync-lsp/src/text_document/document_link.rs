@@ -12,7 +12,7 @@ use crate::connection::Callback;
 use serde::{Deserialize, Serialize};
 use super::{TextDocumentIdentifer, Range, DocumentUri};
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct DocumentLinkOptions {
     resolve_provider: bool
@@ -63,7 +63,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`TextDocumentIdentifer`] of the document that has been opened.
     ///     * `return` - A list of links to display.
-
     pub fn on_document_link(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer) -> Vec<DocumentLink>) {
         self.text_document.document_link.set_callback(Callback::request(move |server, params: DocumentLinkParams| {
             callback(server, params.text_document)
@@ -77,18 +76,9 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`DocumentLink`] to resolve.
     ///     * `return` - The resolved link.
-
     pub fn on_document_link_resolve(&mut self, callback: fn(&mut Server<T>, DocumentLink) -> DocumentLink) {
         self.text_document.resolve_document_link.set_callback(Callback::request(move |server, params| {
             callback(server, params)
         }));
     }
-}
-
-impl Default for DocumentLinkOptions {
-    fn default() -> Self {
-        Self {
-            resolve_provider: false
-        }
-    }
 }
\ No newline at end of file
@@ -38,10 +38,26 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`TextDocumentIdentifer`] of the target document.
     ///     * `return` - A list of symbols to display.
-
     pub fn on_document_symbol(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer) -> Vec<SymbolInformation>) {
         self.text_document.document_symbol.set_callback(Callback::request(move |server, params: DocumentSymbolParams| {
             callback(server, params.text_document)
         }))
     }
+}
+
+impl<T: TypeProvider + Clone + Send> Server<T> {
+
+    /// Sets a callback to [compute document symbols](self), which is run on a worker thread
+    /// if enabled via [`Server::set_worker_threads`].
+    /// 
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as document symbols are requested:
+    ///     * A snapshot of the server state.
+    ///     * The [`TextDocumentIdentifer`] of the target document.
+    ///     * `return` - A list of symbols to display.
+    pub fn on_document_symbol_concurrent(&mut self, callback: fn(&T, TextDocumentIdentifer) -> Vec<SymbolInformation>) {
+        self.text_document.document_symbol.set_callback(Server::concurrent(move |state, params: DocumentSymbolParams| {
+            callback(state, params.text_document)
+        }))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,349 @@
+//! implementation of a built-in store for open text documents.
+//!
+//! # Usage
+//! Once enabled via [`Server::set_document_store`], the server keeps track of all open documents
+//! by applying `textDocument/didOpen`, `textDocument/didChange` and `textDocument/didClose`
+//! notifications before the corresponding user callbacks are invoked. Changes are interpreted according to
+//! the [`TextDocumentSyncKind`] set via [`Server::set_document_sync`].
+//! The documents can be accessed via [`Server::documents`].
+//!
+//! Regardless of the store, the server tracks the version of each open document, which is available via
+//! [`Connection::version`]. Changes, which don't increase the version of a document, are rejected with a logged error
+//! and won't be passed to [`Server::on_change`]. Request handlers may call [`Connection::computed_for`] to mark
+//! their result as computed for a specific version, in which case the client receives [`ErrorCode::ContentModified`]
+//! instead of the result, if the document has been changed in the meantime. Long running handlers may also check
+//! [`Connection::invalidated`] to stop early, if a change to their document is already waiting to be processed.
+//!
+//! # Example
+//! ```
+//! use sync_lsp::{Transport, TypeProvider, Server};
+//!
+//! struct MyServerState;
+//!
+//! #[sync_lsp::type_provider]
+//! impl TypeProvider for MyServerState {}
+//!
+//! fn main() {
+//!     let transport = Transport::stdio();
+//!     let mut server = Server::new(MyServerState, transport);
+//!     server.set_document_store(true);
+//!
+//!     server.on_change(|server, document, _| {
+//!         if let Some(document) = server.documents().get(&document.uri) {
+//!             log::info!("{} now has {} bytes", document.uri(), document.text().len());
+//!         }
+//!     });
+//!
+//!     server.serve().unwrap();
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::collections::hash_map::Values;
+use log::error;
+use serde::Deserialize;
+use crate::{Connection, Server, TypeProvider};
+use crate::ErrorCode;
+use crate::connection::{RpcConnection, MessageQueue};
+use super::did_change::{TextDocumentContentChangeEvent, DidChangeOptions};
+use super::did_open::TextDocumentItem;
+use super::{DocumentUri, Rope, TextDocumentSyncKind, VersionedTextDocumentIdentifier};
+
+/// All documents, which are currently open on the client.
+#[derive(Default)]
+pub struct TextDocuments {
+    enabled: bool,
+    documents: HashMap<DocumentUri, TextDocument>
+}
+
+/// A text document, which is kept in sync with the client.
+#[derive(Debug, Clone)]
+pub struct TextDocument {
+    uri: DocumentUri,
+    language_id: String,
+    version: i32,
+    text: Rope
+}
+
+/// The versions of all open documents, which are tracked regardless of the store.
+#[derive(Default)]
+pub(crate) struct DocumentVersions {
+    versions: HashMap<DocumentUri, i32>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueuedChange {
+    text_document: VersionedTextDocumentIdentifier
+}
+
+impl<T: TypeProvider> Connection<T> {
+
+    /// Returns the current version of an open [document](self).
+    pub fn version(&self, uri: &DocumentUri) -> Option<i32> {
+        self.versions.versions.get(uri).copied()
+    }
+
+    /// Marks the result of the current request as computed for a version of a [document](self).
+    /// If the document has been changed since, or a change is waiting to be processed once the request handler returns,
+    /// the client receives [`ErrorCode::ContentModified`] instead of the result.
+    ///
+    /// # Arguments
+    /// * `uri` - The uri of the document the result is based on.
+    /// * `version` - The version of the document the result is based on.
+    pub fn computed_for(&mut self, uri: DocumentUri, version: i32) {
+        if self.current_request.is_some() {
+            self.result_version = Some((uri, version));
+        }
+    }
+
+    /// Checks whether a change to the [document](self) of the current request is waiting to be processed,
+    /// which means that the result would be outdated. In this case [`ErrorCode::ContentModified`] is returned to the client,
+    /// regardless of what the request handler returns. This is only the case for requests with a `textDocument` parameter.
+    pub fn invalidated(&mut self) -> bool {
+        if self.request_document.is_none() { return false }
+        self.scan_queue();
+        self.modified_by_queue()
+    }
+
+    /// Checks whether the document of the current request is modified by a change in the last scanned queue.
+    pub(crate) fn modified_by_queue(&mut self) -> bool {
+        let Some(uri) = self.request_document.clone() else { return false };
+        let version = self.version(&uri).unwrap_or(i32::MIN);
+
+        if !self.versions.modified(&uri, version, &self.queue) {
+            return false
+        }
+
+        self.error::<()>(
+            ErrorCode::ContentModified,
+            format!("{uri} has been modified")
+        );
+        true
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets whether the server should keep track of all open [documents](self).
+    /// This is disabled by default.
+    ///
+    /// # Argument
+    /// * `value` - If `true`, documents and [notebooks](crate::notebook_document::notebooks) will be stored
+    ///   and updated before user callbacks are invoked.
+    pub fn set_document_store(&mut self, value: bool) {
+        self.text_document.documents.enabled = value;
+        self.notebook_document.notebooks.enabled = value;
+        if value {
+            self.text_document.did_open.set_static_registration();
+            self.text_document.did_change.set_static_registration();
+            self.text_document.did_close.set_static_registration();
+        }
+    }
+
+    /// Returns all open [documents](self), which is empty unless [`Server::set_document_store`] has been enabled.
+    pub fn documents(&self) -> &TextDocuments {
+        &self.text_document.documents
+    }
+}
+
+impl TextDocuments {
+
+    /// Returns the document with the given uri, if it is open.
+    pub fn get(&self, uri: &DocumentUri) -> Option<&TextDocument> {
+        self.documents.get(uri)
+    }
+
+    /// Returns an iterator over all open documents.
+    pub fn iter(&self) -> Values<'_, DocumentUri, TextDocument> {
+        self.documents.values()
+    }
+
+    /// Returns the number of open documents.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Returns whether no documents are open.
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    pub(crate) fn open(&mut self, item: &TextDocumentItem) {
+        if !self.enabled { return }
+        self.documents.insert(item.uri.clone(), TextDocument {
+            uri: item.uri.clone(),
+            language_id: item.language_id.clone(),
+            version: item.version,
+            text: Rope::from(item.text.as_str())
+        });
+    }
+
+    pub(crate) fn change(&mut self, uri: &DocumentUri, version: i32, changes: &[TextDocumentContentChangeEvent], sync_kind: TextDocumentSyncKind) {
+        if !self.enabled { return }
+        let Some(document) = self.documents.get_mut(uri) else {
+            return error!("Received changes for {uri}, which is not open");
+        };
+
+        match sync_kind {
+            TextDocumentSyncKind::None => return,
+            TextDocumentSyncKind::Full => if let Some(change) = changes.last() {
+                document.text = Rope::from(change.text.as_str());
+            },
+            TextDocumentSyncKind::Incremental => for change in changes {
+                document.apply(change);
+            }
+        }
+
+        document.version = version;
+    }
+
+    pub(crate) fn close(&mut self, uri: &DocumentUri) {
+        if !self.enabled { return }
+        self.documents.remove(uri);
+    }
+}
+
+impl DocumentVersions {
+    pub(crate) fn open(&mut self, uri: &DocumentUri, version: i32) {
+        self.versions.insert(uri.clone(), version);
+    }
+
+    /// Tracks the version of a change and returns whether it is newer than the current version.
+    pub(crate) fn change(&mut self, uri: &DocumentUri, version: i32) -> bool {
+        match self.versions.get_mut(uri) {
+            Some(current) if version <= *current => {
+                error!("Rejected change of {uri} to version {version}, which isn't newer than version {current}");
+                false
+            },
+            Some(current) => {
+                *current = version;
+                true
+            },
+            None => {
+                self.versions.insert(uri.clone(), version);
+                true
+            }
+        }
+    }
+
+    pub(crate) fn close(&mut self, uri: &DocumentUri) {
+        self.versions.remove(uri);
+    }
+
+    /// Returns the uris of all open documents.
+    pub(crate) fn uris(&self) -> Vec<DocumentUri> {
+        self.versions.keys().cloned().collect()
+    }
+
+    /// Checks whether a document has been changed after a version, either by a change that has already been
+    /// processed or by one of the queued notifications.
+    pub(crate) fn modified(&self, uri: &DocumentUri, version: i32, queue: &MessageQueue) -> bool {
+        if self.versions.get(uri).is_some_and(|current| *current > version) {
+            return true
+        }
+
+        queue.notifications().any(|(method, params)| method == DidChangeOptions::METHOD && QueuedChange::deserialize(params)
+            .is_ok_and(|change| change.text_document.uri == *uri && change.text_document.version > version))
+    }
+}
+
+impl TextDocument {
+
+    /// Returns the uri of this document.
+    pub fn uri(&self) -> &DocumentUri {
+        &self.uri
+    }
+
+    /// Returns the language identifier of this document.
+    pub fn language_id(&self) -> &str {
+        &self.language_id
+    }
+
+    /// Returns the version of this document, which increases after each change.
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    /// Returns the content of this document, which may be used to convert positions and offsets.
+    pub fn text(&self) -> &Rope {
+        &self.text
+    }
+
+    fn apply(&mut self, change: &TextDocumentContentChangeEvent) {
+        match change.range.as_ref() {
+            Some(range) => self.text.replace(range, &change.text),
+            None => self.text = Rope::from(change.text.as_str())
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a TextDocuments {
+    type Item = &'a TextDocument;
+    type IntoIter = Values<'a, DocumentUri, TextDocument>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DocumentVersions;
+    use crate::connection::MessageQueue;
+    use crate::text_document::DocumentUri;
+
+    fn uri(value: &str) -> DocumentUri {
+        DocumentUri::parse(value).unwrap()
+    }
+
+    fn queue(messages: &[&str]) -> MessageQueue {
+        MessageQueue::parse(0, messages.iter().map(|message| message.as_bytes()))
+    }
+
+    fn change(uri: &str, version: i32) -> String {
+        format!(r#"{{"jsonrpc":"2.0","method":"textDocument/didChange","params":{{"textDocument":{{"uri":"{uri}","version":{version}}},"contentChanges":[]}}}}"#)
+    }
+
+    #[test]
+    fn rejects_changes_that_are_not_newer() {
+        let mut versions = DocumentVersions::default();
+        let a = uri("file:///a.rs");
+        versions.open(&a, 1);
+        assert!(!versions.change(&a, 1));
+        assert!(versions.change(&a, 2));
+        assert!(!versions.change(&a, 1));
+        versions.close(&a);
+        assert!(versions.uris().is_empty());
+    }
+
+    #[test]
+    fn detects_processed_changes() {
+        let mut versions = DocumentVersions::default();
+        let a = uri("file:///a.rs");
+        versions.open(&a, 1);
+        assert!(!versions.modified(&a, 1, &queue(&[])));
+        versions.change(&a, 3);
+        assert!(versions.modified(&a, 2, &queue(&[])));
+        assert!(!versions.modified(&a, 3, &queue(&[])));
+    }
+
+    #[test]
+    fn detects_queued_changes() {
+        let mut versions = DocumentVersions::default();
+        let a = uri("file:///a.rs");
+        versions.open(&a, 1);
+
+        let newer = change("file:///a.rs", 2);
+        let other = change("file:///b.rs", 2);
+        let older = change("file:///a.rs", 1);
+        let encoded = change("FILE:///a%2Ers", 2);
+        let request = r#"{"jsonrpc":"2.0","id":1,"method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///a.rs","version":2}}}"#;
+        let unrelated = r#"{"jsonrpc":"2.0","method":"textDocument/didSave","params":{"textDocument":{"uri":"file:///a.rs","version":2}}}"#;
+
+        assert!(versions.modified(&a, 1, &queue(&[&other, &newer])));
+        assert!(versions.modified(&a, 1, &queue(&[&encoded])));
+        assert!(!versions.modified(&a, 1, &queue(&[&other, &older])));
+        assert!(!versions.modified(&a, 1, &queue(&[request, unrelated, "{invalid"])));
+    }
+}
@@ -52,7 +52,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * `document` - The [`TextDocumentIdentifer`] of the target document.
     ///     * `options` - The [`FormattingOptions`] that specify how the document should be formatted.
     ///     * `return` - A list of edits to apply to the document.
-
     pub fn on_formatting(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, FormattingOptions) -> Vec<TextEdit>) {
         self.text_document.formatting.set_callback(Callback::request(move |server, params: DocumentFormattingParams | {
             callback(server, params.text_document, params.options)
@@ -55,10 +55,27 @@ impl<T: TypeProvider> Server<T> {
     ///     * The [`TextDocumentIdentifer`] of the target document.
     ///     * The [`Position`] of the cursor.
     ///     * `return` - The hover information to display.
-
     pub fn on_hover(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Position) -> Hover) {
         self.text_document.hover.set_callback(Callback::request(move |server, params: TextDocumentPositionParams| {
             callback(server, params.text_document, params.position)
         }))
     }
+}
+
+impl<T: TypeProvider + Clone + Send> Server<T> {
+
+    /// Sets a callback to [compute hover information](self), which is run on a worker thread
+    /// if enabled via [`Server::set_worker_threads`].
+    /// 
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as hover information is requested:
+    ///     * A snapshot of the server state.
+    ///     * The [`TextDocumentIdentifer`] of the target document.
+    ///     * The [`Position`] of the cursor.
+    ///     * `return` - The hover information to display.
+    pub fn on_hover_concurrent(&mut self, callback: fn(&T, TextDocumentIdentifer, Position) -> Hover) {
+        self.text_document.hover.set_callback(Server::concurrent(move |state, params: TextDocumentPositionParams| {
+            callback(state, params.text_document, params.position)
+        }))
+    }
 }
\ No newline at end of file
@@ -14,6 +14,10 @@ use self::formatting::DocumentFormattingOptions;
 use self::hover::HoverOptions;
 use self::on_type_formatting::DocumentOnTypeFormattingOptions;
 use self::publish_diagnostics::PublishDiagnostics;
+use self::documents::TextDocuments;
+use self::diagnostic::{DiagnosticOptions, DiagnosticFallback};
+use self::selector::DocumentSelector;
+use crate::lifecycle::initialize::DynamicRegistration;
 use self::range_formatting::RangeFormattingOptions;
 use self::references::ReferenceOptions;
 use self::rename::RenameOptions;
@@ -50,8 +54,19 @@ pub mod code_action;
 pub mod code_lens;
 pub mod document_link;
 pub mod rename;
+pub mod documents;
+pub mod diagnostic;
+pub mod diagnostic_collection;
+mod uri;
+mod rope;
+mod selector;
 
-pub type DocumentUri = String;
+pub use self::uri::{Uri, InvalidUri};
+pub use self::rope::{Rope, Chunks, Lines};
+pub use self::selector::DocumentFilter;
+
+/// The uri of a document, which is represented by a normalized [`Uri`].
+pub type DocumentUri = Uri;
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -67,7 +82,7 @@ pub struct TextEdit {
     pub new_text: String,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Location {
     pub uri: DocumentUri,
     pub range: Range,
@@ -78,13 +93,13 @@ pub struct TextDocumentIdentifer {
     pub uri: DocumentUri,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct Position {
     pub line: i32,
     pub character: i32,
 }
 
-#[derive(Deserialize, Serialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct Range {
     pub start: Position,
     pub end: Position,
@@ -99,11 +114,15 @@ pub struct VersionedTextDocumentIdentifier {
 #[derive(Deserialize, Default)]
 #[serde(default, rename_all = "camelCase")]
 pub(super) struct TextDocumentClientCapabilities {
+    synchronization: DynamicRegistration,
+    diagnostic: Option<DynamicRegistration>,
     completion: CompletionCapabilities
 }
 
 pub(super) struct TextDocumentService<T: TypeProvider> {
     pub(super) sync_kind: TextDocumentSyncKind,
+    pub(super) documents: TextDocuments,
+    pub(super) selector: DocumentSelector,
     pub(super) did_open: Endpoint<T, DidOpenOptions>,
     pub(super) did_change: Endpoint<T, DidChangeOptions>,
     pub(super) will_save: Endpoint<T, WillSaveOptions>,
@@ -112,6 +131,8 @@ pub(super) struct TextDocumentService<T: TypeProvider> {
     pub(super) did_close: Endpoint<T, DidCloseOptions>,
     #[allow(unused)]
     publish_diagnostics: PublishDiagnostics,
+    pub(super) diagnostic: Endpoint<T, DiagnosticOptions>,
+    pub(super) diagnostic_fallback: DiagnosticFallback<T>,
     pub(super) completion: Endpoint<T, CompletionOptions>,
     pub(super) resolve_completion: Endpoint<T, ResolveCompletionOptions>,
     pub(super) hover: Endpoint<T, HoverOptions>,
@@ -159,6 +180,7 @@ impl<T: TypeProvider> TextDocumentService<T> {
             WillSaveWaitUntilOptions::METHOD => Some(self.will_save_wait_until.callback()),
             DidSaveOptions::METHOD => Some(self.did_save.callback()),
             DidCloseOptions::METHOD => Some(self.did_close.callback()),
+            DiagnosticOptions::METHOD => Some(self.diagnostic.callback()),
             CompletionOptions::METHOD => Some(self.completion.callback()),
             ResolveCompletionOptions::METHOD => Some(self.resolve_completion.callback()),
             HoverOptions::METHOD => Some(self.hover.callback()),
@@ -176,6 +198,7 @@ impl<T: TypeProvider> TextDocumentService<T> {
             DocumentLinkOptions::METHOD => Some(self.document_link.callback()),
             DocumentLinkResolveOptions::METHOD => Some(self.resolve_document_link.callback()),
             RenameOptions::METHOD => Some(self.rename.callback()),
+            DocumentSelector::METHOD => Some(DocumentSelector::callback()),
             _ => None
         }
     }
@@ -185,6 +208,8 @@ impl<T: TypeProvider> Default for TextDocumentService<T> {
     fn default() -> Self {
         TextDocumentService {
             sync_kind: Default::default(),
+            documents: Default::default(),
+            selector: Default::default(),
             did_open: DidOpenOptions::endpoint(),
             did_change: DidChangeOptions::endpoint(),
             will_save: WillSaveOptions::endpoint(),
@@ -192,6 +217,8 @@ impl<T: TypeProvider> Default for TextDocumentService<T> {
             did_save: DidSaveOptions::endpoint(),
             did_close: DidCloseOptions::endpoint(),
             publish_diagnostics: Default::default(),
+            diagnostic: DiagnosticOptions::endpoint(),
+            diagnostic_fallback: Default::default(),
             completion: CompletionOptions::endpoint(),
             resolve_completion: ResolveCompletionOptions::endpoint(),
             hover: HoverOptions::endpoint(),
@@ -49,8 +49,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * `position` - The [`Position`] of the cursor.
     ///     * `options` - The [`FormattingOptions`] that specify how the document should be formatted.
     ///     * `return` - A list of edits to apply to the document.
-
-
     pub fn on_type_formatting(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Position, String, FormattingOptions) -> Vec<TextEdit>) {
         self.text_document.on_type_formatting.set_callback(Callback::request(move |server, params: DocumentOnTypeFormattingParams | {
             callback(server, params.text_document, params.position, params.ch, params.options)
@@ -61,7 +59,6 @@ impl<T: TypeProvider> Server<T> {
     /// 
     /// # Argument
     /// * `value` - The first trigger character that triggers formatting.
-
     pub fn set_on_type_formatting_first_trigger_character(&mut self, value: String) {
         self.text_document.on_type_formatting.options_mut().first_trigger_character = value;
     }
@@ -70,7 +67,6 @@ impl<T: TypeProvider> Server<T> {
     ///     
     /// # Argument
     /// * `value` - Additional characters that trigger formatting.
-
     pub fn set_on_type_formatting_more_trigger_characters(&mut self, value: Vec<String>) {
         self.text_document.on_type_formatting.options_mut().more_trigger_character = value;
     }
@@ -3,6 +3,8 @@
 //! # Usage
 //! A server can publish diagnostics for a specific document via [`Connection::publish_diagnostics`]
 //! in any callback. The client will then display these diagnostics in the editor.
+//! Since each call replaces all diagnostics of the document, servers with multiple sources of diagnostics
+//! may prefer the [diagnostic collection](super::diagnostic_collection), which merges them.
 
 use serde::{Serialize, Deserialize};
 use serde_repr::{Serialize_repr, Deserialize_repr};
@@ -10,44 +12,134 @@ use serde_repr::{Serialize_repr, Deserialize_repr};
 use crate::{Connection, TypeProvider};
 use crate::connection::RpcConnection;
 
-use super::{DocumentUri, Range};
+use super::{DocumentUri, Uri, Range, Location};
 
 #[derive(Default, Clone)]
 pub(super) struct PublishDiagnostics;
 
 /// The diagnostic information.
-#[derive(Deserialize, Serialize, Debug)]
-pub struct Diagnostic {
+/// Additional data of type [`TypeProvider::DiagnosticData`] is preserved between a
+/// [`Connection::publish_diagnostics`] notification and a subsequent
+/// [`CodeActionContext`](super::code_action::CodeActionContext).
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic<D> {
     /// A range in the document that contains the diagnostic message.
     pub range: Range,
     /// The severity of the diagnostic.
     pub severity: Option<DiagnosticSeverity>,
     /// A optional code to identify the diagnostic.
-    pub code: Option<String>,
+    pub code: Option<DiagnosticCode>,
+    /// An optional link to a description of the diagnostic code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code_description: Option<CodeDescription>,
     /// A string describing the source of this diagnostic.
     pub source: Option<String>,
     /// A human-readable string describing the diagnostic.
     pub message: String,
+    /// Additional metadata about the diagnostic.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<DiagnosticTag>,
+    /// Related locations, like the previous declaration of a duplicate symbol.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_information: Vec<DiagnosticRelatedInformation>,
+    /// Arbitrary data as defined in [`TypeProvider::DiagnosticData`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<D>
 }
 
 /// The diagnostic severity.
 #[repr(i32)]
-#[derive(Deserialize_repr, Serialize_repr, Debug)]
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DiagnosticSeverity {
+    /// Reports an error.
     Error = 1,
+    /// Reports a warning.
     Warning = 2,
+    /// Reports an information.
     Information = 3,
+    /// Reports a hint.
     Hint = 4
 }
 
+/// The code of a diagnostic, which may either be a number or a string.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum DiagnosticCode {
+    Number(i32),
+    String(String)
+}
+
+/// A link to a description of a diagnostic code.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CodeDescription {
+    /// The uri of the description, which is usually a website.
+    pub href: Uri
+}
+
+/// Additional metadata about a diagnostic, which may change how it is displayed.
+#[repr(i32)]
+#[derive(Deserialize_repr, Serialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticTag {
+    /// Unused or unnecessary code, which may be faded out.
+    Unnecessary = 1,
+    /// Deprecated or obsolete code, which may be struck through.
+    Deprecated = 2
+}
+
+/// A location related to a diagnostic.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DiagnosticRelatedInformation {
+    /// The location of the related information.
+    pub location: Location,
+    /// The message of the related information.
+    pub message: String
+}
+
+impl<D> Default for Diagnostic<D> {
+    fn default() -> Self {
+        Diagnostic {
+            range: Range::default(),
+            severity: None,
+            code: None,
+            code_description: None,
+            source: None,
+            message: String::new(),
+            tags: Vec::new(),
+            related_information: Vec::new(),
+            data: None
+        }
+    }
+}
+
+impl From<i32> for DiagnosticCode {
+    fn from(code: i32) -> Self {
+        DiagnosticCode::Number(code)
+    }
+}
+
+impl From<String> for DiagnosticCode {
+    fn from(code: String) -> Self {
+        DiagnosticCode::String(code)
+    }
+}
+
+impl From<&str> for DiagnosticCode {
+    fn from(code: &str) -> Self {
+        DiagnosticCode::String(code.to_string())
+    }
+}
+
 #[derive(Serialize)]
-struct PublishDiagnosticsParams {
-    uri: DocumentUri,
-    diagnostics: Vec<Diagnostic>
+pub(super) struct PublishDiagnosticsParams<D> {
+    pub(super) uri: DocumentUri,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) version: Option<i32>,
+    pub(super) diagnostics: D
 }
 
 impl PublishDiagnostics {
-    const METHOD: &'static str = "textDocument/publishDiagnostics";
+    pub(super) const METHOD: &'static str = "textDocument/publishDiagnostics";
 }
 
 impl<T: TypeProvider> Connection<T> {
@@ -56,13 +148,14 @@ impl<T: TypeProvider> Connection<T> {
     /// 
     /// # Arguments
     /// * `uri` - The [`DocumentUri`] of the document to publish diagnostics for.
+    /// * `version` - The version of the document the diagnostics have been computed for, if known.
     /// * `diagnostics` - A list of diagnostics to publish.
-
-    pub fn publish_diagnostics(&mut self, uri: DocumentUri, diagnostics: Vec<Diagnostic>) {
+    pub fn publish_diagnostics(&mut self, uri: DocumentUri, version: Option<i32>, diagnostics: Vec<Diagnostic<T::DiagnosticData>>) {
         self.notify(
             PublishDiagnostics::METHOD,
             PublishDiagnosticsParams {
                 uri,
+                version,
                 diagnostics
             }
         );
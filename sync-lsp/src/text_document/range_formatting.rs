@@ -43,7 +43,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * `range` - The [`Range`] that should be formatted.
     ///     * `options` - The [`FormattingOptions`] that specify how the document should be formatted.
     ///     * `return` - A list of edits to apply to the document.
-
     pub fn on_range_formatting(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Range, FormattingOptions) -> Vec<TextEdit>) {
         self.text_document.range_formatting.set_callback(Callback::request(move |server, params: DocumentRangeFormattingParams | {
             callback(server, params.text_document, params.range, params.options)
@@ -3,6 +3,7 @@
 //! # Usage
 //! A client can resolve references to a symbol via [`Server::on_references`], which could
 //! be used to implement "Find all references" functionality.
+//! Large results may be streamed to the client via [partial results](crate::window::partial_result).
 
 use crate::TypeProvider;
 use crate::{Server, connection::Endpoint};
@@ -49,10 +50,28 @@ impl<T: TypeProvider> Server<T> {
     ///    * The [`Position`] of the cursor.
     ///    * The [`ReferenceContext`] that specifies which references should be returned.
     ///    * `return` - A list of locations that reference the symbol at the given position.
-
     pub fn on_references(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Position, context: ReferenceContext) -> Vec<Location>) {
         self.text_document.references.set_callback(Callback::request(move |server, params: ReferenceParams| {
             callback(server, params.text_document, params.position, params.context)
         }))
     }
+}
+
+impl<T: TypeProvider + Clone + Send> Server<T> {
+
+    /// Sets a callback to [resolve references](self), which is run on a worker thread
+    /// if enabled via [`Server::set_worker_threads`].
+    /// 
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as references are requested:
+    ///    * A snapshot of the server state.
+    ///    * The [`TextDocumentIdentifer`] of the target document.
+    ///    * The [`Position`] of the cursor.
+    ///    * The [`ReferenceContext`] that specifies which references should be returned.
+    ///    * `return` - A list of locations that reference the symbol at the given position.
+    pub fn on_references_concurrent(&mut self, callback: fn(&T, TextDocumentIdentifer, Position, context: ReferenceContext) -> Vec<Location>) {
+        self.text_document.references.set_callback(Server::concurrent(move |state, params: ReferenceParams| {
+            callback(state, params.text_document, params.position, params.context)
+        }))
+    }
 }
\ No newline at end of file
@@ -41,7 +41,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * The [`Position`] of the cursor.
     ///     * The new name of the symbol.
     ///     * `return` - A [`WorkspaceEdit`] that contains the changes to apply to the workspace.
-
     pub fn on_rename(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Position, String) -> WorkspaceEdit) {
         self.text_document.rename.set_callback(Callback::request(move |server, params: RenameParams | {
             callback(server, params.text_document, params.position, params.new_name)
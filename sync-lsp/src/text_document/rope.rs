@@ -0,0 +1,602 @@
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::mem::take;
+use std::ops::Range as Bytes;
+use super::{Position, Range};
+
+/// The maximum size of a leaf in bytes, which leaves may exceed by a factor of two when they are edited in place.
+const MAX_LEAF: usize = 1024;
+
+/// A text, which is stored as a balanced tree of chunks, so that edits and conversions between
+/// [`Position`]s and byte offsets take logarithmic time instead of being proportional to the length of the text.
+/// Positions use utf-16 code units for characters and `\n`, `\r\n` and `\r` as line breaks as specified by the protocol.
+///
+/// # Example
+/// ```
+/// use sync_lsp::text_document::{Rope, Position, Range};
+///
+/// let mut rope = Rope::from("fn main() {\r\n    println!(\"😀\");\n}");
+/// let position = Position { line: 1, character: 14 };
+///
+/// assert_eq!(rope.offset(&position), 27);
+/// assert_eq!(rope.position(27).character, 14);
+/// assert_eq!(rope.lines().collect::<Vec<_>>(), ["fn main() {", "    println!(\"😀\");", "}"]);
+///
+/// let range = Range { start: Position { line: 1, character: 4 }, end: Position { line: 1, character: 12 } };
+/// assert_eq!(rope.slice(&range), "println!");
+///
+/// rope.replace(&range, "print!");
+/// assert_eq!(rope.line(1).unwrap(), "    print!(\"😀\");");
+/// ```
+#[derive(Clone, Default)]
+pub struct Rope {
+    root: Node
+}
+
+/// An iterator over the chunks of a [`Rope`], which yields consecutive parts of the text.
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+    first: Option<&'a str>
+}
+
+/// An iterator over the lines of a [`Rope`], which yields each line without its line break.
+pub struct Lines<'a> {
+    rope: &'a Rope,
+    line: usize
+}
+
+#[derive(Clone)]
+enum Node {
+    Leaf(String, Summary),
+    Branch(Box<Node>, Box<Node>, Summary, usize)
+}
+
+/// Information about a part of the text, which can be combined with the information about the following part.
+#[derive(Clone, Copy, Default)]
+struct Summary {
+    bytes: usize,
+    utf16: usize,
+    breaks: usize,
+    /// The number of utf-16 code units after the last line break.
+    tail: usize,
+    starts_with_lf: bool,
+    ends_with_cr: bool
+}
+
+impl Rope {
+
+    /// Creates an empty rope.
+    pub fn new() -> Rope {
+        Rope::default()
+    }
+
+    /// Returns the length of the text in bytes.
+    pub fn len(&self) -> usize {
+        self.root.summary().bytes
+    }
+
+    /// Returns whether the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of lines, which is one more than the number of line breaks.
+    pub fn len_lines(&self) -> usize {
+        self.root.summary().breaks + 1
+    }
+
+    /// Converts a position into a byte offset. Positions beyond the end of a line
+    /// or the text are clamped to the end of that line or the text.
+    pub fn offset(&self, position: &Position) -> usize {
+        let start = match self.line_start(position.line.max(0) as usize) {
+            Some(start) => start,
+            None => return self.len()
+        };
+
+        let mut offset = start;
+        let mut character = position.character.max(0) as usize;
+        for chunk in self.chunks_at(start) {
+            for char in chunk.chars() {
+                if char == '\n' || char == '\r' || character < char.len_utf16() {
+                    return offset
+                }
+                character -= char.len_utf16();
+                offset += char.len_utf8();
+            }
+        }
+
+        offset
+    }
+
+    /// Converts a byte offset into a position, which is clamped to the end of the text.
+    pub fn position(&self, offset: usize) -> Position {
+        let summary = self.root.prefix(offset.min(self.len()));
+        Position {
+            line: summary.breaks as i32,
+            character: summary.tail as i32
+        }
+    }
+
+    /// Returns the text within a range.
+    pub fn slice(&self, range: &Range) -> String {
+        self.slice_bytes(self.bytes(range))
+    }
+
+    /// Returns the text within a range of bytes.
+    pub fn slice_bytes(&self, range: Bytes<usize>) -> String {
+        let mut slice = String::with_capacity(range.len());
+        let mut remaining = range.end.saturating_sub(range.start);
+        for chunk in self.chunks_at(range.start) {
+            if remaining <= chunk.len() {
+                slice.push_str(&chunk[..remaining]);
+                break
+            }
+            slice.push_str(chunk);
+            remaining -= chunk.len();
+        }
+        slice
+    }
+
+    /// Returns a line without its line break.
+    pub fn line(&self, index: usize) -> Option<String> {
+        let start = self.line_start(index)?;
+        let end = self.line_start(index + 1).unwrap_or(self.len());
+        let mut line = self.slice_bytes(start..end);
+        if line.ends_with('\n') { line.pop(); }
+        if line.ends_with('\r') { line.pop(); }
+        Some(line)
+    }
+
+    /// Returns an iterator over all lines.
+    pub fn lines(&self) -> Lines<'_> {
+        Lines {
+            rope: self,
+            line: 0
+        }
+    }
+
+    /// Returns an iterator over all chunks of the text.
+    pub fn chunks(&self) -> Chunks<'_> {
+        self.chunks_at(0)
+    }
+
+    /// Replaces the text within a range.
+    pub fn replace(&mut self, range: &Range, text: &str) {
+        self.replace_bytes(self.bytes(range), text)
+    }
+
+    /// Replaces the text within a range of bytes.
+    pub fn replace_bytes(&mut self, range: Bytes<usize>, text: &str) {
+        let end = range.end.min(self.len());
+        let start = range.start.min(end);
+
+        if self.root.replace_in_leaf(start, end, text) {
+            return
+        }
+
+        let (left, rest) = take(&mut self.root).split(start);
+        let (_, right) = rest.split(end - start);
+        self.root = Node::concat(Node::concat(left, Node::build(text)), right);
+
+        let leaves = self.len() / (MAX_LEAF / 2) + 1;
+        if self.root.depth() > 2 * leaves.ilog2() as usize + 8 {
+            self.root = Node::build(&self.to_string());
+        }
+    }
+
+    fn bytes(&self, range: &Range) -> Bytes<usize> {
+        let start = self.offset(&range.start);
+        start..self.offset(&range.end).max(start)
+    }
+
+    fn line_start(&self, line: usize) -> Option<usize> {
+        match line {
+            0 => Some(0),
+            line if line > self.root.summary().breaks => None,
+            line => Some(self.root.line_start(line))
+        }
+    }
+
+    fn chunks_at(&self, mut offset: usize) -> Chunks<'_> {
+        let mut stack = Vec::new();
+        let mut node = &self.root;
+        while let Node::Branch(left, right, ..) = node {
+            if offset < left.summary().bytes {
+                stack.push(right.as_ref());
+                node = left;
+            } else {
+                offset -= left.summary().bytes;
+                node = right;
+            }
+        }
+
+        Chunks {
+            stack,
+            first: node.text().get(offset.min(node.text().len())..)
+        }
+    }
+}
+
+impl Node {
+    fn leaf(text: String) -> Node {
+        let summary = Summary::of(&text);
+        Node::Leaf(text, summary)
+    }
+
+    fn branch(left: Node, right: Node) -> Node {
+        let summary = left.summary().combine(right.summary());
+        let depth = left.depth().max(right.depth()) + 1;
+        Node::Branch(Box::new(left), Box::new(right), summary, depth)
+    }
+
+    /// Builds a balanced tree from a text.
+    fn build(text: &str) -> Node {
+        let mut nodes = Vec::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            let mut end = rest.len().min(MAX_LEAF);
+            while !rest.is_char_boundary(end) { end -= 1 }
+            nodes.push(Node::leaf(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+
+        while nodes.len() > 1 {
+            let mut pairs = Vec::with_capacity(nodes.len() / 2 + 1);
+            let mut nodes_iter = nodes.into_iter();
+            while let Some(left) = nodes_iter.next() {
+                pairs.push(match nodes_iter.next() {
+                    Some(right) => Node::branch(left, right),
+                    None => left
+                });
+            }
+            nodes = pairs;
+        }
+
+        nodes.pop().unwrap_or_default()
+    }
+
+    /// Concatenates two trees, merging them if both are small leaves.
+    fn concat(left: Node, right: Node) -> Node {
+        match (left, right) {
+            (left, right) if right.summary().bytes == 0 => left,
+            (left, right) if left.summary().bytes == 0 => right,
+            (Node::Leaf(mut left, _), Node::Leaf(right, _)) if left.len() + right.len() <= MAX_LEAF => {
+                left.push_str(&right);
+                Node::leaf(left)
+            },
+            (left, right) => Node::branch(left, right)
+        }
+    }
+
+    fn split(self, offset: usize) -> (Node, Node) {
+        match self {
+            Node::Leaf(mut text, _) => {
+                let mut offset = offset.min(text.len());
+                while !text.is_char_boundary(offset) { offset -= 1 }
+                let right = text.split_off(offset);
+                (Node::leaf(text), Node::leaf(right))
+            },
+            Node::Branch(left, right, ..) => {
+                let bytes = left.summary().bytes;
+                if offset <= bytes {
+                    let (left, middle) = left.split(offset);
+                    (left, Node::concat(middle, *right))
+                } else {
+                    let (middle, right) = right.split(offset - bytes);
+                    (Node::concat(*left, middle), right)
+                }
+            }
+        }
+    }
+
+    /// Replaces a range within a single leaf without restructuring the tree,
+    /// which returns `false` if the range spans multiple leaves or the leaf would grow too large.
+    fn replace_in_leaf(&mut self, start: usize, end: usize, text: &str) -> bool {
+        match self {
+            Node::Leaf(leaf, summary) => {
+                if leaf.len() - (end - start) + text.len() > 2 * MAX_LEAF
+                    || !leaf.is_char_boundary(start) || !leaf.is_char_boundary(end) {
+                    return false
+                }
+                leaf.replace_range(start..end, text);
+                *summary = Summary::of(leaf);
+                true
+            },
+            Node::Branch(left, right, summary, _) => {
+                let bytes = left.summary().bytes;
+                let replaced = if end <= bytes {
+                    left.replace_in_leaf(start, end, text)
+                } else if start >= bytes {
+                    right.replace_in_leaf(start - bytes, end - bytes, text)
+                } else {
+                    false
+                };
+
+                if replaced {
+                    *summary = left.summary().combine(right.summary());
+                }
+
+                replaced
+            }
+        }
+    }
+
+    /// Returns the offset after the nth line break, which has to exist.
+    fn line_start(&self, line: usize) -> usize {
+        match self {
+            Node::Leaf(text, _) => {
+                let mut breaks = 0;
+                let mut chars = text.char_indices().peekable();
+                while let Some((index, char)) = chars.next() {
+                    if char == '\n' || (char == '\r' && chars.peek().is_none_or(|(_, next)| *next != '\n')) {
+                        breaks += 1;
+                        if breaks == line {
+                            return index + 1
+                        }
+                    }
+                }
+                text.len()
+            },
+            Node::Branch(left, right, ..) => {
+                let joined = left.summary().ends_with_cr && right.summary().starts_with_lf;
+                let breaks = left.summary().breaks - joined as usize;
+                if line <= breaks {
+                    left.line_start(line)
+                } else {
+                    left.summary().bytes + right.line_start(line - breaks)
+                }
+            }
+        }
+    }
+
+    /// Returns the summary of the text before an offset.
+    fn prefix(&self, offset: usize) -> Summary {
+        match self {
+            Node::Leaf(text, _) => {
+                let mut offset = offset.min(text.len());
+                while !text.is_char_boundary(offset) { offset -= 1 }
+                Summary::of(&text[..offset])
+            },
+            Node::Branch(left, right, ..) => {
+                let bytes = left.summary().bytes;
+                if offset <= bytes {
+                    left.prefix(offset)
+                } else {
+                    left.summary().combine(right.prefix(offset - bytes))
+                }
+            }
+        }
+    }
+
+    fn summary(&self) -> Summary {
+        match self {
+            Node::Leaf(_, summary) | Node::Branch(_, _, summary, _) => *summary
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match self {
+            Node::Leaf(..) => 0,
+            Node::Branch(_, _, _, depth) => *depth
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            Node::Leaf(text, _) => text,
+            Node::Branch(..) => ""
+        }
+    }
+}
+
+impl Summary {
+    fn of(text: &str) -> Summary {
+        let mut summary = Summary {
+            bytes: text.len(),
+            starts_with_lf: text.starts_with('\n'),
+            ends_with_cr: text.ends_with('\r'),
+            ..Default::default()
+        };
+
+        let mut chars = text.chars().peekable();
+        while let Some(char) = chars.next() {
+            summary.utf16 += char.len_utf16();
+            summary.tail += char.len_utf16();
+            if char == '\n' || (char == '\r' && chars.peek() != Some(&'\n')) {
+                summary.breaks += 1;
+                summary.tail = 0;
+            }
+        }
+
+        summary
+    }
+
+    /// Combines this summary with the summary of the following text, where a `\r` at the end of this text
+    /// and a `\n` at the start of the following text form a single line break.
+    fn combine(self, next: Summary) -> Summary {
+        if self.bytes == 0 { return next }
+        if next.bytes == 0 { return self }
+        let joined = self.ends_with_cr && next.starts_with_lf;
+        Summary {
+            bytes: self.bytes + next.bytes,
+            utf16: self.utf16 + next.utf16,
+            breaks: self.breaks + next.breaks - joined as usize,
+            tail: if next.breaks > 0 { next.tail } else { self.tail + next.utf16 },
+            starts_with_lf: self.starts_with_lf,
+            ends_with_cr: next.ends_with_cr
+        }
+    }
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Node::leaf(String::new())
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(first) = self.first.take() {
+            return Some(first)
+        }
+
+        let mut node = self.stack.pop()?;
+        while let Node::Branch(left, right, ..) = node {
+            self.stack.push(right);
+            node = left;
+        }
+
+        Some(node.text())
+    }
+}
+
+impl Iterator for Lines<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.rope.line(self.line)?;
+        self.line += 1;
+        Some(line)
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Rope {
+            root: Node::build(text)
+        }
+    }
+}
+
+impl From<String> for Rope {
+    fn from(text: String) -> Self {
+        Rope::from(text.as_str())
+    }
+}
+
+impl Display for Rope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl Debug for Rope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(&self.to_string(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rope, MAX_LEAF};
+    use crate::text_document::{Position, Range};
+
+    fn position(line: i32, character: i32) -> Position {
+        Position { line, character }
+    }
+
+    fn line_character(position: Position) -> (i32, i32) {
+        (position.line, position.character)
+    }
+
+    fn range(start: Position, end: Position) -> Range {
+        Range { start, end }
+    }
+
+    /// Converts an offset into a position by scanning the whole text.
+    fn naive_position(text: &str, offset: usize) -> Position {
+        let prefix = text[..offset].replace("\r\n", "\n").replace('\r', "\n");
+        let line = prefix.split('\n').count() as i32 - 1;
+        let character = prefix.rsplit('\n').next().unwrap().encode_utf16().count() as i32;
+        position(line, character)
+    }
+
+    #[test]
+    fn counts_characters_in_utf16() {
+        let rope = Rope::from("a😀b\näöü");
+        assert_eq!(rope.offset(&position(0, 1)), 1);
+        assert_eq!(rope.offset(&position(0, 3)), 5);
+        assert_eq!(rope.offset(&position(1, 2)), 11);
+        assert_eq!(line_character(rope.position(5)), (0, 3));
+        assert_eq!(line_character(rope.position(11)), (1, 2));
+
+        // A position within a surrogate pair refers to the start of the character.
+        assert_eq!(rope.offset(&position(0, 2)), 1);
+    }
+
+    #[test]
+    fn accepts_all_line_breaks() {
+        let rope = Rope::from("a\r\nb\rc\nd");
+        assert_eq!(rope.len_lines(), 4);
+        assert_eq!(rope.lines().collect::<Vec<_>>(), ["a", "b", "c", "d"]);
+        assert_eq!(rope.offset(&position(1, 0)), 3);
+        assert_eq!(rope.offset(&position(2, 0)), 5);
+        assert_eq!(rope.offset(&position(3, 0)), 7);
+        assert_eq!(line_character(rope.position(3)), (1, 0));
+        assert_eq!(line_character(rope.position(7)), (3, 0));
+    }
+
+    #[test]
+    fn clamps_positions_and_offsets() {
+        let rope = Rope::from("ab\r\ncd");
+        assert_eq!(rope.offset(&position(0, 10)), 2);
+        assert_eq!(rope.offset(&position(5, 0)), rope.len());
+        assert_eq!(rope.offset(&position(-1, -1)), 0);
+        assert_eq!(line_character(rope.position(100)), (1, 2));
+        assert_eq!(rope.line(2), None);
+    }
+
+    #[test]
+    fn joins_line_breaks_across_leaves() {
+        let text = format!("{}\r\n😀", "x".repeat(MAX_LEAF - 1));
+        let rope = Rope::from(text.as_str());
+        assert!(rope.chunks().count() > 1);
+        assert_eq!(rope.len_lines(), 2);
+        assert_eq!(rope.offset(&position(1, 0)), MAX_LEAF + 1);
+        assert_eq!(line_character(rope.position(rope.len())), (1, 2));
+        assert_eq!(rope.line(0).unwrap(), "x".repeat(MAX_LEAF - 1));
+    }
+
+    #[test]
+    fn replaces_ranges() {
+        let mut rope = Rope::from("a\r");
+        rope.replace(&range(position(1, 0), position(1, 0)), "\nb");
+        assert_eq!(rope.to_string(), "a\r\nb");
+        assert_eq!(rope.len_lines(), 2);
+
+        rope.replace(&range(position(0, 1), position(1, 0)), "");
+        assert_eq!(rope.to_string(), "ab");
+        assert_eq!(rope.len_lines(), 1);
+
+        // The end of a range is never before its start.
+        rope.replace(&range(position(0, 1), position(0, 0)), "😀");
+        assert_eq!(rope.to_string(), "a😀b");
+    }
+
+    #[test]
+    fn matches_a_string_after_many_edits() {
+        let line = "fn main() {\r\n    println!(\"äöü 😀\");\n}\r";
+        let mut text = line.repeat(200);
+        let mut rope = Rope::from(text.as_str());
+
+        for step in 0..300 {
+            let start = (step * 7919) % (text.len() + 1);
+            let end = (start + step % 50).min(text.len());
+            let (start, end) = (text.floor_char_boundary(start), text.floor_char_boundary(end));
+            let insert = if step % 3 == 0 { "" } else { &line[..line.floor_char_boundary(step % line.len())] };
+
+            text.replace_range(start..end, insert);
+            rope.replace_bytes(start..end, insert);
+
+            assert_eq!(rope.len(), text.len());
+            assert_eq!(line_character(rope.position(start)), line_character(naive_position(&text, start)));
+            if !(text[..start].ends_with('\r') && text[start..].starts_with('\n')) {
+                assert_eq!(rope.offset(&naive_position(&text, start)), start);
+            }
+        }
+
+        assert_eq!(rope.to_string(), text);
+        assert_eq!(rope.lines().collect::<Vec<_>>(), text.replace("\r\n", "\n").replace('\r', "\n").split('\n').collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,401 @@
+//! implementation of document selectors for the synchronization of text documents.
+//!
+//! # Usage
+//! By default, the client synchronizes all documents with the server. Via [`Server::set_document_selector`],
+//! synchronization can be restricted to documents matching at least one [`DocumentFilter`].
+//! If the client supports dynamic registration, the selector is registered via `client/registerCapability`
+//! once the server has been initialized. Otherwise the synchronization is announced statically
+//! and notifications for documents, which don't match the selector, are ignored by the server,
+//! which means that neither the callbacks nor the [document store](super::documents) will see them.
+//! If the client rejects the registration, it is registered again without a selector,
+//! so that the server falls back to ignoring documents, which don't match it.
+//!
+//! # Example
+//! ```
+//! use sync_lsp::{Transport, TypeProvider, Server};
+//! use sync_lsp::text_document::DocumentFilter;
+//!
+//! struct MyServerState;
+//!
+//! #[sync_lsp::type_provider]
+//! impl TypeProvider for MyServerState {}
+//!
+//! fn main() {
+//!     let transport = Transport::stdio();
+//!     let mut server = Server::new(MyServerState, transport);
+//!     server.set_document_selector(vec![
+//!         DocumentFilter {
+//!             scheme: Some("file".to_string()),
+//!             pattern: Some("**/*.foo".to_string()),
+//!             ..Default::default()
+//!         }
+//!     ]);
+//!
+//!     server.on_open(|_, document| {
+//!         log::info!("{} matches the selector", document.uri);
+//!     });
+//!
+//!     server.serve().unwrap();
+//! }
+//! ```
+
+use std::collections::HashSet;
+use log::{error, warn};
+use serde::{Serialize, Deserialize};
+use crate::{Server, TypeProvider};
+use crate::connection::{Callback, RpcConnection};
+use crate::lifecycle::initialize::ClientCapabilities;
+use super::did_open::TextDocumentItem;
+use super::{DocumentUri, TextDocumentSyncKind};
+use super::{DidOpenOptions, DidChangeOptions, DidCloseOptions, DidSaveOptions, WillSaveOptions, WillSaveWaitUntilOptions};
+
+/// Restricts a feature to documents with a specific language, scheme or path.
+/// All fields, which are set, have to match.
+///
+/// # Example
+/// ```
+/// use sync_lsp::text_document::{DocumentFilter, DocumentUri};
+///
+/// let filter = DocumentFilter {
+///     pattern: Some("**/*.{foo,bar}".to_string()),
+///     ..Default::default()
+/// };
+///
+/// let uri: DocumentUri = "file:///home/user/src/main.foo".parse().unwrap();
+/// assert!(filter.matches(&uri, "foo"));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentFilter {
+    /// A language id, like `rust`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// A uri scheme, like `file` or `untitled`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+    /// A glob pattern, which is matched against the path of a document, like `**/*.foo`.
+    /// Patterns support `*`, `**`, `?`, `{a,b}` and character ranges like `[0-9]` or `[!a-z]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>
+}
+
+/// The document selector of a server and the documents it ignores because they don't match it.
+#[derive(Default)]
+pub(crate) struct DocumentSelector {
+    filters: Vec<DocumentFilter>,
+    dynamic: bool,
+    ignored: HashSet<DocumentUri>
+}
+
+#[derive(Serialize)]
+struct RegistrationParams<'a> {
+    registrations: Vec<Registration<'a>>
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Registration<'a> {
+    id: &'static str,
+    method: &'static str,
+    register_options: TextDocumentSyncRegistrationOptions<'a>
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TextDocumentSyncRegistrationOptions<'a> {
+    document_selector: Option<&'a [DocumentFilter]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sync_kind: Option<TextDocumentSyncKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_text: Option<bool>
+}
+
+impl DocumentFilter {
+
+    /// Checks whether a document matches this filter.
+    ///
+    /// # Arguments
+    /// * `uri` - The uri of the document.
+    /// * `language_id` - The language id of the document.
+    /// * `return` - `true`, if all fields of this filter, which are set, match the document.
+    pub fn matches(&self, uri: &DocumentUri, language_id: &str) -> bool {
+        self.language.as_ref().is_none_or(|language| language == language_id)
+            && self.scheme.as_ref().is_none_or(|scheme| scheme.eq_ignore_ascii_case(uri.scheme()))
+            && self.pattern.as_ref().is_none_or(|pattern| glob(pattern, &uri.decoded_path()))
+    }
+}
+
+impl DocumentSelector {
+
+    pub(crate) const METHOD: &'static str = "client/registerCapability";
+
+    /// Tracks an opened document and returns whether it should be synchronized.
+    pub(super) fn open(&mut self, document: &TextDocumentItem) -> bool {
+        if self.filters.is_empty() || self.filters.iter().any(|filter| filter.matches(&document.uri, &document.language_id)) {
+            self.ignored.remove(&document.uri);
+            return true
+        }
+
+        self.ignored.insert(document.uri.clone());
+        false
+    }
+
+    /// Returns whether a document has been opened without matching the selector.
+    pub(super) fn ignores(&self, uri: &DocumentUri) -> bool {
+        self.ignored.contains(uri)
+    }
+
+    /// Stops tracking a closed document and returns whether it has been synchronized.
+    pub(super) fn close(&mut self, uri: &DocumentUri) -> bool {
+        !self.ignored.remove(uri)
+    }
+
+    pub(crate) fn callback<T: TypeProvider>() -> Callback<Server<T>> {
+        Callback::response_result(|server: &mut Server<T>, fallback: bool, result: Result<(), _>| {
+            let Err(error) = result else { return };
+            if fallback {
+                error!("Failed to register document synchronization: {}", error.message);
+            } else {
+                warn!("Failed to register document synchronization, retrying without a selector: {}", error.message);
+                server.register_document_sync(true);
+            }
+        })
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Restricts the [synchronization of documents](self) to documents matching at least one filter.
+    /// An empty selector, which is the default, matches all documents.
+    ///
+    /// # Argument
+    /// * `filters` - The filters of the selector.
+    pub fn set_document_selector(&mut self, filters: Vec<DocumentFilter>) {
+        self.text_document.selector.filters = filters;
+    }
+
+    /// Decides whether the synchronization is registered dynamically,
+    /// which requires a selector and a client supporting it.
+    pub(crate) fn negotiate_document_sync(&mut self, capabilities: &ClientCapabilities) -> bool {
+        let selector = &mut self.text_document.selector;
+        selector.dynamic = !selector.filters.is_empty() && capabilities.text_document.synchronization.dynamic_registration;
+        selector.dynamic
+    }
+
+    /// Registers the synchronization of documents matching the selector, if it has been negotiated.
+    ///
+    /// # Argument
+    /// * `fallback` - Whether the registration is retried without a selector,
+    ///   which leaves the filtering of documents to the server.
+    pub(crate) fn register_document_sync(&mut self, fallback: bool) {
+        let text_document = &self.text_document;
+        if !text_document.selector.dynamic { return }
+
+        let options = |sync_kind, include_text| TextDocumentSyncRegistrationOptions {
+            document_selector: Some(text_document.selector.filters.as_slice()).filter(|_| !fallback),
+            sync_kind,
+            include_text
+        };
+
+        let mut registrations = Vec::new();
+        if text_document.did_open.static_registration() || text_document.did_close.static_registration() {
+            registrations.push(Registration { id: DidOpenOptions::METHOD, method: DidOpenOptions::METHOD, register_options: options(None, None) });
+            registrations.push(Registration { id: DidCloseOptions::METHOD, method: DidCloseOptions::METHOD, register_options: options(None, None) });
+        }
+
+        if !matches!(text_document.sync_kind, TextDocumentSyncKind::None) {
+            registrations.push(Registration { id: DidChangeOptions::METHOD, method: DidChangeOptions::METHOD, register_options: options(Some(text_document.sync_kind), None) });
+        }
+
+        if text_document.will_save.static_registration() {
+            registrations.push(Registration { id: WillSaveOptions::METHOD, method: WillSaveOptions::METHOD, register_options: options(None, None) });
+        }
+
+        if text_document.will_save_wait_until.static_registration() {
+            registrations.push(Registration { id: WillSaveWaitUntilOptions::METHOD, method: WillSaveWaitUntilOptions::METHOD, register_options: options(None, None) });
+        }
+
+        if text_document.did_save.static_registration() {
+            let include_text = text_document.did_save.options().include_text;
+            registrations.push(Registration { id: DidSaveOptions::METHOD, method: DidSaveOptions::METHOD, register_options: options(None, Some(include_text)) });
+        }
+
+        let params = RegistrationParams { registrations };
+        self.connection.request(DocumentSelector::METHOD, fallback, params);
+    }
+}
+
+/// Matches a path against a glob pattern after expanding all alternatives.
+fn glob(pattern: &str, path: &str) -> bool {
+    let path: Vec<char> = path.chars().collect();
+    expand(pattern).iter().any(|pattern| {
+        let pattern: Vec<char> = pattern.chars().collect();
+        glob_match(&pattern, &path)
+    })
+}
+
+/// Expands alternatives like `{a,b}`, which may be nested, into separate patterns.
+fn expand(pattern: &str) -> Vec<String> {
+    let Some(start) = pattern.find('{') else {
+        return vec![pattern.to_string()]
+    };
+
+    let mut depth = 0;
+    let mut alternatives = Vec::new();
+    let mut last = start + 1;
+    for (index, char) in pattern.char_indices().skip_while(|(index, _)| *index <= start) {
+        match char {
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(&pattern[last..index]);
+                last = index + 1;
+            },
+            '}' => {
+                alternatives.push(&pattern[last..index]);
+                let (prefix, suffix) = (&pattern[..start], &pattern[index + 1..]);
+                return alternatives.into_iter()
+                    .flat_map(|alternative| expand(&format!("{prefix}{alternative}{suffix}")))
+                    .collect()
+            },
+            _ => {}
+        }
+    }
+
+    vec![pattern.to_string()]
+}
+
+fn glob_match(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', rest @ ..] => {
+            let (rest, segments) = match rest {
+                ['/', rest @ ..] => (rest, true),
+                rest => (rest, false)
+            };
+            (0..=path.len())
+                .filter(|&index| !segments || index == 0 || path[index - 1] == '/')
+                .any(|index| glob_match(rest, &path[index..]))
+        },
+        ['*', rest @ ..] => (0..=path.len())
+            .take_while(|&index| index == 0 || path[index - 1] != '/')
+            .any(|index| glob_match(rest, &path[index..])),
+        ['?', rest @ ..] => matches!(path, [char, ..] if *char != '/') && glob_match(rest, &path[1..]),
+        ['[', class @ ..] => {
+            let Some(end) = class.iter().skip(1).position(|char| *char == ']').map(|end| end + 1) else {
+                return path.first() == Some(&'[') && glob_match(class, &path[1..])
+            };
+            let Some(&char) = path.first().filter(|char| **char != '/') else { return false };
+            let (negated, set) = match &class[..end] {
+                ['!', set @ ..] => (true, set),
+                set => (false, set)
+            };
+            let mut matched = false;
+            let mut index = 0;
+            while index < set.len() {
+                if index + 2 < set.len() && set[index + 1] == '-' {
+                    matched |= (set[index]..=set[index + 2]).contains(&char);
+                    index += 3;
+                } else {
+                    matched |= set[index] == char;
+                    index += 1;
+                }
+            }
+            matched != negated && glob_match(&class[end + 1..], &path[1..])
+        },
+        [char, rest @ ..] => path.first() == Some(char) && glob_match(rest, &path[1..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob, expand, DocumentFilter, DocumentSelector};
+    use crate::text_document::DocumentUri;
+    use crate::text_document::did_open::TextDocumentItem;
+
+    fn document(uri: &str, language_id: &str) -> TextDocumentItem {
+        TextDocumentItem {
+            uri: DocumentUri::parse(uri).unwrap(),
+            language_id: language_id.to_string(),
+            version: 1,
+            text: String::new()
+        }
+    }
+
+    #[test]
+    fn expands_alternatives() {
+        assert_eq!(expand("*.rs"), ["*.rs"]);
+        assert_eq!(expand("*.{rs,toml}"), ["*.rs", "*.toml"]);
+        assert_eq!(expand("{src,tests}/*.{a,b}"), ["src/*.a", "src/*.b", "tests/*.a", "tests/*.b"]);
+        assert_eq!(expand("a{b,{c,d}e}"), ["ab", "ace", "ade"]);
+        assert_eq!(expand("a{,b}"), ["a", "ab"]);
+        assert_eq!(expand("a{b,c"), ["a{b,c"]);
+    }
+
+    #[test]
+    fn matches_wildcards_within_segments() {
+        assert!(glob("*.rs", "main.rs"));
+        assert!(!glob("*.rs", "src/main.rs"));
+        assert!(glob("src/*", "src/main.rs"));
+        assert!(!glob("src/*", "src/bin/main.rs"));
+        assert!(glob("?.rs", "a.rs"));
+        assert!(!glob("?.rs", "ab.rs"));
+        assert!(!glob("a?b", "a/b"));
+    }
+
+    #[test]
+    fn matches_globstars_across_segments() {
+        assert!(glob("**/*.rs", "/home/user/main.rs"));
+        assert!(glob("**/*.rs", "main.rs"));
+        assert!(glob("/home/**/main.rs", "/home/main.rs"));
+        assert!(glob("/home/**/main.rs", "/home/user/src/main.rs"));
+        assert!(!glob("/home/**/main.rs", "/home/user/my_main.rs"));
+        assert!(glob("/home/**", "/home/user/main.rs"));
+        assert!(!glob("**/*.rs", "/home/user/main.rs.bak"));
+    }
+
+    #[test]
+    fn matches_character_classes() {
+        assert!(glob("file[0-9].rs", "file7.rs"));
+        assert!(!glob("file[0-9].rs", "fileA.rs"));
+        assert!(glob("file[!0-9].rs", "fileA.rs"));
+        assert!(!glob("file[!0-9].rs", "file7.rs"));
+        assert!(glob("file[abc].rs", "fileb.rs"));
+        assert!(!glob("a[/]b", "a/b"));
+        assert!(glob("file[.rs", "file[.rs"));
+        assert!(glob("**/*.{rs,[ch]}", "/src/main.c"));
+    }
+
+    #[test]
+    fn matches_filters_against_documents() {
+        let filter = DocumentFilter {
+            language: Some("rust".to_string()),
+            scheme: Some("file".to_string()),
+            pattern: Some("**/*.rs".to_string())
+        };
+
+        let uri = DocumentUri::parse("FILE:///my%20project/main.rs").unwrap();
+        assert!(filter.matches(&uri, "rust"));
+        assert!(!filter.matches(&uri, "toml"));
+        assert!(!filter.matches(&DocumentUri::parse("untitled:/main.rs").unwrap(), "rust"));
+        assert!(DocumentFilter::default().matches(&uri, "toml"));
+    }
+
+    #[test]
+    fn ignores_documents_not_matching_the_selector() {
+        let mut selector = DocumentSelector::default();
+        assert!(selector.open(&document("file:///a.txt", "text")));
+
+        selector.filters = vec![DocumentFilter {
+            pattern: Some("**/*.rs".to_string()),
+            ..Default::default()
+        }];
+
+        assert!(selector.open(&document("file:///a.rs", "rust")));
+        assert!(!selector.open(&document("file:///b.txt", "text")));
+        assert!(!selector.ignores(&DocumentUri::parse("file:///a.rs").unwrap()));
+        assert!(selector.ignores(&DocumentUri::parse("file:///b.txt").unwrap()));
+        assert!(!selector.close(&DocumentUri::parse("file:///b.txt").unwrap()));
+        assert!(!selector.ignores(&DocumentUri::parse("file:///b.txt").unwrap()));
+        assert!(selector.close(&DocumentUri::parse("file:///a.rs").unwrap()));
+    }
+}
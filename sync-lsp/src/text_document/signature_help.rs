@@ -68,7 +68,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * The [`TextDocumentIdentifer`] of the target document.
     ///     * The [`Position`] of the cursor.
     ///     * `return` - The signature help to display.
-
     pub fn on_signature_help(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, Position) -> SignatureHelp) {
         self.text_document.signature_help.set_callback(Callback::request(move |server, params: TextDocumentPositionParams| {
             callback(server, params.text_document, params.position)
@@ -79,7 +78,6 @@ impl<T: TypeProvider> Server<T> {
     /// 
     /// # Argument
     /// * `value` - The characters that trigger signature help.
-
     pub fn set_signature_help_trigger_characters(&mut self, value: Vec<String>) {
         self.text_document.signature_help.options_mut().trigger_characters = value;
     }
@@ -0,0 +1,460 @@
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::Error as DeserializeError;
+
+/// A validated and normalized uri as used by the language server protocol to identify documents.
+/// Uris are normalized when they are created, which lowercases the scheme and host, uppercases
+/// percent-encoded bytes, decodes percent-encoded unreserved characters and encodes characters,
+/// which aren't allowed in uris, like spaces and non-ascii characters. Drive letters of `file` uris
+/// are lowercased as well.
+/// Therefore two uris are equal if they only differ in the case of their scheme or in their encoding.
+///
+/// # Example
+/// ```
+/// use std::path::Path;
+/// use sync_lsp::text_document::Uri;
+///
+/// let uri = Uri::parse("FILE:///home/user/my%20file.rs").unwrap();
+/// assert_eq!(uri, Uri::parse("file:///home/user/my file.rs").unwrap());
+/// assert_eq!(uri.scheme(), "file");
+///
+/// # #[cfg(unix)]
+/// assert_eq!(uri.to_file_path().unwrap(), Path::new("/home/user/my file.rs"));
+/// # #[cfg(unix)]
+/// assert_eq!(Uri::from_file_path("/home/user/my file.rs"), Some(uri));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Uri {
+    value: String,
+    scheme: usize
+}
+
+/// The error returned if a string isn't a valid [`Uri`], which is the case if it lacks a valid scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidUri(String);
+
+impl Uri {
+
+    /// Parses and normalizes a uri.
+    ///
+    /// # Argument
+    /// * `value` - The uri to parse, which has to start with a scheme followed by a colon.
+    /// * `return` - The normalized uri or an error if no valid scheme is present.
+    pub fn parse(value: &str) -> Result<Uri, InvalidUri> {
+        let scheme = match value.find(':') {
+            Some(scheme) if valid_scheme(&value[..scheme]) => scheme,
+            _ => return Err(InvalidUri(value.to_string()))
+        };
+
+        let mut normalized = value[..scheme].to_ascii_lowercase();
+        normalized.push(':');
+
+        let mut rest = &value[scheme + 1..];
+        if let Some(hierarchical) = rest.strip_prefix("//") {
+            let end = hierarchical.find(['/', '?', '#']).unwrap_or(hierarchical.len());
+            let (authority, path) = hierarchical.split_at(end);
+            let host = authority.rfind('@').map_or(0, |index| index + 1);
+            normalized.push_str("//");
+            encode_into(&mut normalized, &authority[..host], is_uri_char);
+            encode_into(&mut normalized, &authority[host..].to_lowercase(), is_uri_char);
+            rest = path;
+        }
+
+        let path = normalized.len();
+        encode_into(&mut normalized, rest, is_uri_char);
+        if normalized.starts_with("file:") {
+            normalize_drive_letter(&mut normalized, path);
+        }
+
+        Ok(Uri {
+            value: normalized,
+            scheme
+        })
+    }
+
+    /// Creates a `file` uri from an absolute path. All characters of the path, which aren't allowed in uris,
+    /// are percent-encoded, including `%` itself, so that [`Uri::to_file_path`] returns the original path.
+    ///
+    /// # Argument
+    /// * `path` - An absolute path to a file or directory.
+    /// * `return` - The uri or `None` if the path isn't absolute.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(unix)] {
+    /// use std::path::Path;
+    /// use sync_lsp::text_document::Uri;
+    ///
+    /// for path in ["/tmp/100%41.txt", "/tmp/a%2Fb", "/tmp/my file.rs", "/tmp/äöü/日本.rs"] {
+    ///     let uri = Uri::from_file_path(path).unwrap();
+    ///     assert_eq!(uri.to_file_path().unwrap(), Path::new(path));
+    ///     assert_eq!(Uri::parse(uri.as_str()).unwrap(), uri);
+    /// }
+    ///
+    /// let uri = Uri::from_file_path("/tmp/100%41 ä.txt").unwrap();
+    /// assert_eq!(uri.as_str(), "file:///tmp/100%2541%20%C3%A4.txt");
+    /// # }
+    /// ```
+    pub fn from_file_path(path: impl AsRef<Path>) -> Option<Uri> {
+        let path = path.as_ref();
+        if !path.is_absolute() {
+            return None
+        }
+
+        let mut value = "file://".to_string();
+
+        #[cfg(windows)]
+        {
+            let mut path = path.to_str()?.replace('\\', "/");
+            if let Some(verbatim) = path.strip_prefix("//?/") {
+                path = match verbatim.strip_prefix("UNC/") {
+                    Some(unc) => format!("//{unc}"),
+                    None => verbatim.to_string()
+                };
+            }
+
+            match path.strip_prefix("//") {
+                Some(unc) => {
+                    let (host, share) = unc.split_at(unc.find('/').unwrap_or(unc.len()));
+                    escape_into(&mut value, host.to_lowercase().as_bytes(), is_uri_char);
+                    escape_into(&mut value, share.as_bytes(), is_path_char);
+                },
+                None => {
+                    value.push('/');
+                    escape_into(&mut value, path.as_bytes(), is_path_char);
+                    normalize_drive_letter(&mut value, 7);
+                }
+            }
+        }
+
+        #[cfg(not(windows))]
+        escape_into(&mut value, path.as_os_str().as_encoded_bytes(), is_path_char);
+
+        Some(Uri {
+            value,
+            scheme: 4
+        })
+    }
+
+    /// Converts a `file` uri to a path. The query and fragment of the uri are ignored.
+    ///
+    /// # Argument
+    /// * `return` - The path or `None` if this uri doesn't refer to a local file.
+    pub fn to_file_path(&self) -> Option<PathBuf> {
+        if self.scheme() != "file" {
+            return None
+        }
+
+        let rest = &self.value[self.scheme + 1..];
+        let end = rest.find(['?', '#']).unwrap_or(rest.len());
+        let rest = rest[..end].strip_prefix("//")?;
+        let (host, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+        if path.contains("%2F") || path.contains("%5C") || path.contains("%00") {
+            return None
+        }
+
+        let path = decode(path);
+
+        #[cfg(windows)]
+        {
+            let path = String::from_utf8(path).ok()?.replace('/', "\\");
+            if !host.is_empty() && host != "localhost" {
+                return Some(PathBuf::from(format!("\\\\{host}{path}")))
+            }
+
+            let drive = path.strip_prefix('\\')?;
+            let letter = drive.chars().next()?;
+            if !letter.is_ascii_alphabetic() || !drive[1..].starts_with(':') {
+                return None
+            }
+
+            Some(PathBuf::from(drive))
+        }
+
+        #[cfg(unix)]
+        {
+            use std::ffi::OsString;
+            use std::os::unix::ffi::OsStringExt;
+
+            if (!host.is_empty() && host != "localhost") || path.is_empty() {
+                return None
+            }
+
+            Some(PathBuf::from(OsString::from_vec(path)))
+        }
+
+        #[cfg(not(any(unix, windows)))]
+        {
+            if (!host.is_empty() && host != "localhost") || path.is_empty() {
+                return None
+            }
+
+            String::from_utf8(path).ok().map(PathBuf::from)
+        }
+    }
+
+    /// Returns the lowercase scheme of this uri, for example `file` or `untitled`.
+    pub fn scheme(&self) -> &str {
+        &self.value[..self.scheme]
+    }
+
+    /// Returns the percent-encoded path of this uri, which excludes the authority, query and fragment.
+    pub fn path(&self) -> &str {
+        let rest = &self.value[self.scheme + 1..];
+        let rest = match rest.strip_prefix("//") {
+            Some(rest) => &rest[rest.find(['/', '?', '#']).unwrap_or(rest.len())..],
+            None => rest
+        };
+        &rest[..rest.find(['?', '#']).unwrap_or(rest.len())]
+    }
+
+    /// Returns the decoded path of this uri, which is used to match glob patterns.
+    pub(super) fn decoded_path(&self) -> String {
+        String::from_utf8_lossy(&decode(self.path())).into_owned()
+    }
+
+    /// Returns the normalized uri as a string.
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Lowercases the drive letter of windows paths and decodes the colon following it, since clients differ in how they encode them.
+fn normalize_drive_letter(value: &mut String, path: usize) {
+    let bytes = &value.as_bytes()[path..];
+    if bytes.len() < 3 || bytes[0] != b'/' || !bytes[1].is_ascii_alphabetic() {
+        return
+    }
+
+    let colon = match &bytes[2..] {
+        [b':', ..] => 1,
+        [b'%', b'3', b'A', ..] => 3,
+        _ => return
+    };
+
+    if bytes.get(2 + colon).is_some_and(|byte| !matches!(byte, b'/' | b'?' | b'#')) {
+        return
+    }
+
+    let drive = format!("/{}:", bytes[1].to_ascii_lowercase() as char);
+    value.replace_range(path..path + 2 + colon, &drive);
+}
+
+fn valid_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|char| char.is_ascii_alphabetic())
+        && chars.all(|char| char.is_ascii_alphanumeric() || matches!(char, '+' | '-' | '.'))
+}
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+fn is_path_char(byte: u8) -> bool {
+    is_unreserved(byte) || matches!(byte, b'/' | b':' | b'@' | b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=')
+}
+
+fn is_uri_char(byte: u8) -> bool {
+    is_path_char(byte) || matches!(byte, b'?' | b'#' | b'[' | b']')
+}
+
+/// Appends a string to a uri, normalizes existing percent-encoded bytes and encodes all bytes, which aren't allowed.
+fn encode_into(target: &mut String, value: &str, allowed: fn(u8) -> bool) {
+    let bytes = value.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        let escaped = match byte {
+            b'%' => bytes.get(index + 1..index + 3).and_then(hex),
+            _ => None
+        };
+
+        match escaped {
+            Some(escaped) if is_unreserved(escaped) => {
+                target.push(escaped as char);
+                index += 3;
+                continue
+            },
+            Some(escaped) => {
+                target.push_str(&format!("%{escaped:02X}"));
+                index += 3;
+                continue
+            },
+            None if byte != b'%' && allowed(byte) => target.push(byte as char),
+            None => target.push_str(&format!("%{byte:02X}"))
+        }
+
+        index += 1;
+    }
+}
+
+/// Appends raw bytes to a uri and encodes all bytes, which aren't allowed, including `%`,
+/// since the bytes don't contain any percent-encoded bytes yet.
+fn escape_into(target: &mut String, bytes: &[u8], allowed: fn(u8) -> bool) {
+    for &byte in bytes {
+        match byte {
+            b'%' => target.push_str("%25"),
+            byte if allowed(byte) => target.push(byte as char),
+            byte => target.push_str(&format!("%{byte:02X}"))
+        }
+    }
+}
+
+fn hex(digits: &[u8]) -> Option<u8> {
+    let digits = std::str::from_utf8(digits).ok()?;
+    if !digits.chars().all(|char| char.is_ascii_hexdigit()) {
+        return None
+    }
+    u8::from_str_radix(digits, 16).ok()
+}
+
+fn decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes.get(index + 1..index + 3).and_then(hex).filter(|_| bytes[index] == b'%') {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            },
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    decoded
+}
+
+impl FromStr for Uri {
+    type Err = InvalidUri;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Uri::parse(value)
+    }
+}
+
+impl TryFrom<String> for Uri {
+    type Error = InvalidUri;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Uri::parse(&value)
+    }
+}
+
+impl From<Uri> for String {
+    fn from(uri: Uri) -> Self {
+        uri.value
+    }
+}
+
+impl AsRef<str> for Uri {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Display for Uri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(&self.value)
+    }
+}
+
+impl Debug for Uri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(&self.value, f)
+    }
+}
+
+impl Serialize for Uri {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.value)
+    }
+}
+
+impl<'de> Deserialize<'de> for Uri {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Uri::parse(&value).map_err(D::Error::custom)
+    }
+}
+
+impl Display for InvalidUri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "Invalid uri: {}", self.0)
+    }
+}
+
+impl Error for InvalidUri {}
+
+#[cfg(test)]
+mod tests {
+    use super::Uri;
+
+    fn normalized(value: &str) -> String {
+        Uri::parse(value).unwrap().as_str().to_string()
+    }
+
+    #[test]
+    fn rejects_missing_or_invalid_schemes() {
+        for value in ["", "no-scheme", ":path", "1file:///a", "fi le:///a", "/home/user/a.rs"] {
+            assert!(Uri::parse(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn lowercases_scheme_and_host() {
+        assert_eq!(normalized("FILE:///Home/A.rs"), "file:///Home/A.rs");
+        assert_eq!(normalized("HTTP://User@Example.COM/Path?Q#F"), "http://User@example.com/Path?Q#F");
+        assert_eq!(Uri::parse("Untitled:Untitled-1").unwrap().scheme(), "untitled");
+    }
+
+    #[test]
+    fn normalizes_percent_encoding() {
+        assert_eq!(normalized("file:///a%7eb%2fc%c3%a4"), "file:///a~b%2Fc%C3%A4");
+        assert_eq!(normalized("file:///my file/ä.rs"), "file:///my%20file/%C3%A4.rs");
+        assert_eq!(normalized("file:///100%"), "file:///100%25");
+        assert_eq!(normalized("file:///%zz%4"), "file:///%25zz%254");
+        assert_eq!(Uri::parse("file:///a%20b").unwrap(), Uri::parse("file:///a b").unwrap());
+    }
+
+    #[test]
+    fn normalizes_drive_letters() {
+        assert_eq!(normalized("file:///C:/Users/a.rs"), "file:///c:/Users/a.rs");
+        assert_eq!(normalized("file:///C%3A/Users/a.rs"), "file:///c:/Users/a.rs");
+        assert_eq!(normalized("file:///C%3a"), "file:///c:");
+        assert_eq!(normalized("file:///Cd:/a.rs"), "file:///Cd:/a.rs");
+        assert_eq!(normalized("file:///C:a.rs"), "file:///C:a.rs");
+        assert_eq!(normalized("untitled:/C:/a.rs"), "untitled:/C:/a.rs");
+    }
+
+    #[test]
+    fn splits_path_from_authority_query_and_fragment() {
+        let uri = Uri::parse("file://host/a%20b/c.rs?query#fragment").unwrap();
+        assert_eq!(uri.path(), "/a%20b/c.rs");
+        assert_eq!(uri.decoded_path(), "/a b/c.rs");
+        assert_eq!(Uri::parse("untitled:Untitled-1").unwrap().path(), "Untitled-1");
+        assert_eq!(Uri::parse("file://host").unwrap().path(), "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn converts_file_paths() {
+        use std::path::Path;
+
+        assert_eq!(Uri::from_file_path("relative/a.rs"), None);
+        assert_eq!(Uri::from_file_path("/tmp/a%b [1].rs").unwrap().as_str(), "file:///tmp/a%25b%20%5B1%5D.rs");
+
+        let path = |value: &str| Uri::parse(value).unwrap().to_file_path();
+        assert_eq!(path("file:///tmp/a%20b.rs?query#fragment").unwrap(), Path::new("/tmp/a b.rs"));
+        assert_eq!(path("file://localhost/tmp/a.rs").unwrap(), Path::new("/tmp/a.rs"));
+        assert_eq!(path("file://remote/tmp/a.rs"), None);
+        assert_eq!(path("file:///tmp/a%2Fb.rs"), None);
+        assert_eq!(path("file:///tmp/a%00b.rs"), None);
+        assert_eq!(path("untitled:/tmp/a.rs"), None);
+    }
+}
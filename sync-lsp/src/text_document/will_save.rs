@@ -47,9 +47,9 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * The [`TextDocumentIdentifer`] of the target document.
     ///     * The [`TextDocumentSaveReason`] that specifies why the document is saved. 
-    
     pub fn on_will_save(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, TextDocumentSaveReason)) {
-        self.text_document.will_save.set_callback(Callback::notification(move |server, params: WillSaveTextDocumentParams| {
+        self.text_document.will_save.set_callback(Callback::notification(move |server: &mut Server<T>, params: WillSaveTextDocumentParams| {
+            if server.text_document.selector.ignores(&params.text_document.uri) { return }
             callback(server, params.text_document, params.reason)
         }))
     }
@@ -40,9 +40,9 @@ impl<T: TypeProvider> Server<T> {
     ///     * The [`TextDocumentIdentifer`] of the target document.
     ///     * The [`TextDocumentSaveReason`] that specifies why the document is saved.
     ///     * `return` - A list of edits to apply to the document.
-
     pub fn on_will_save_wait_until(&mut self, callback: fn(&mut Server<T>, TextDocumentIdentifer, TextDocumentSaveReason) -> Vec<TextEdit>) {
-        self.text_document.will_save_wait_until.set_callback(Callback::request(move |server, params: WillSaveWaitUntilTextDocumentParams| {
+        self.text_document.will_save_wait_until.set_callback(Callback::request(move |server: &mut Server<T>, params: WillSaveWaitUntilTextDocumentParams| {
+            if server.text_document.selector.ignores(&params.text_document.uri) { return Vec::new() }
             callback(server, params.text_document, params.reason)
         }))
     }
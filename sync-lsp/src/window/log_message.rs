@@ -11,11 +11,19 @@
 //! info!("This is an info message");
 //! debug!("This is a debug message and will therefore not be shown on release builds");
 //! ```
+//! The level can be changed at any time via [`Server::set_log_level`] and records may additionally
+//! be written to a file using [`Server::set_log_file`]. If the application already uses a different logger,
+//! [`Server::set_global_logger`] disables the automatic setup and [`Server::logger`] returns a [`ClientLogger`],
+//! which can be combined with it.
 
+use std::fs::File;
+use log::{LevelFilter, set_max_level};
 use serde::Serialize;
-use crate::{Connection, TypeProvider};
+use crate::{Connection, Server, TypeProvider};
 use crate::connection::RpcConnection;
 
+pub use crate::connection::ClientLogger;
+
 use super::MessageType;
 
 #[derive(Default)]
@@ -35,7 +43,6 @@ impl<T: TypeProvider> Connection<T> {
     /// # Arguments
     /// * `r#type` - The type of log message.
     /// * `message` - The message to log.
-    
     pub fn log_message(&mut self, r#type: MessageType, message: String) {
         self.notify(
             LogMessage::METHOD,
@@ -47,6 +54,43 @@ impl<T: TypeProvider> Connection<T> {
     }
 }
 
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the maximum level of records that are [forwarded](self) to the client.
+    /// The default is [`LevelFilter::Trace`] on debug builds and [`LevelFilter::Info`] on release builds.
+    /// 
+    /// # Argument
+    /// * `level` - The new level filter.
+    pub fn set_log_level(&mut self, level: LevelFilter) {
+        self.connection.logging.logger().set_level(level);
+        if self.connection.logging.installed {
+            set_max_level(level);
+        }
+    }
+
+    /// Sets a file, to which all [forwarded](self) records are written as well.
+    /// 
+    /// # Argument
+    /// * `file` - The file to write to or `None` to stop writing to the previous file.
+    pub fn set_log_file(&mut self, file: Option<File>) {
+        self.connection.logging.logger().set_file(file);
+    }
+
+    /// Sets whether the [`ClientLogger`] should be installed as the global logger once the server starts.
+    /// If a different logger has already been installed, this will fail with a warning sent to the client.
+    /// 
+    /// # Argument
+    /// * `value` - If `false`, no logger will be installed by this library.
+    pub fn set_global_logger(&mut self, value: bool) {
+        self.connection.logging.global = value;
+    }
+
+    /// Returns a [`ClientLogger`], which forwards [log records](self) to this server's client.
+    pub fn logger(&self) -> ClientLogger {
+        self.connection.logging.logger().clone()
+    }
+}
+
 impl LogMessage {
     const METHOD: &'static str = "window/logMessage";
-}
\ No newline at end of file
+}
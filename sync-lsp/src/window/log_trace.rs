@@ -0,0 +1,62 @@
+//! implementation of the `$/logTrace` and `$/setTrace` notifications.
+//!
+//! # Usage
+//! The client decides how detailed traces should be, either via the initialize request
+//! or later on via `$/setTrace`. Both are handled by this library and [`Connection::log_trace`]
+//! only sends traces, if they are enabled. The current level can be queried via [`Connection::trace`],
+//! which is useful if the verbose part of a trace is expensive to compute.
+//! Additionally [`Server::set_message_tracing`] enables automatic traces for each request and notification,
+//! which include the time it took to handle them.
+
+use serde::Deserialize;
+use crate::{Connection, Server, TypeProvider};
+use crate::connection::{Callback, RpcConnection};
+
+pub use crate::connection::TraceValue;
+
+#[derive(Default)]
+pub(super) struct SetTrace;
+
+#[derive(Deserialize)]
+struct SetTraceParams {
+    value: TraceValue
+}
+
+impl<T: TypeProvider> Connection<T> {
+
+    /// Sends a [trace](self) to the client, unless tracing has been turned off.
+    ///
+    /// # Arguments
+    /// * `message` - The message to trace.
+    /// * `verbose` - Additional information, which is only sent if the client requested verbose traces.
+    pub fn log_trace(&mut self, message: String, verbose: Option<String>) {
+        RpcConnection::trace(self, message, || verbose)
+    }
+
+    /// Returns the current [trace](self) level requested by the client.
+    pub fn trace(&self) -> TraceValue {
+        self.trace
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets whether each request and notification should be [traced](self) automatically,
+    /// including the time it took to handle it. This is disabled by default.
+    ///
+    /// # Argument
+    /// * `value` - If `true`, traces will be sent as long as the client has enabled them.
+    pub fn set_message_tracing(&mut self, value: bool) {
+        self.connection.trace_messages = value;
+    }
+}
+
+impl SetTrace {
+    pub(super) const METHOD: &'static str = "$/setTrace";
+
+    pub(super) fn callback<T: TypeProvider>(&self) -> Callback<Server<T>> {
+        Callback::notification(|server: &mut Server<T>, params: SetTraceParams| {
+            server.connection.trace = params.value;
+        })
+    }
+}
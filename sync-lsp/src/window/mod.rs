@@ -1,5 +1,6 @@
 //! This module contains functionality centered arround logging and ui features.
 
+use serde::Deserialize;
 use serde_repr::Serialize_repr;
 
 use crate::{Server, TypeProvider};
@@ -7,13 +8,20 @@ use crate::connection::Callback;
 
 use self::log_message::LogMessage;
 use self::show_message::ShowMessage;
-use self::show_message_request::ShowMessageRequest;
+use self::show_message_request::{ShowMessageRequest, ShowMessageRequestClientCapabilities};
 use self::telemetry::Telemetry;
+use self::log_trace::SetTrace;
+use self::work_done_progress::{WorkDoneProgressCreate, WorkDoneProgressCancel};
+use self::show_document::{ShowDocument, ShowDocumentClientCapabilities};
 
 pub mod show_message;
 pub mod log_message;
 pub mod telemetry;
 pub mod show_message_request;
+pub mod log_trace;
+pub mod work_done_progress;
+pub mod partial_result;
+pub mod show_document;
 
 /// This struct contains all window endpoints, which are mainly used to resolve callbacks.
 pub(super) struct WindowService<T: TypeProvider> {
@@ -24,11 +32,23 @@ pub(super) struct WindowService<T: TypeProvider> {
     log_message: LogMessage,
     #[allow(unused)]
     telemetry: Telemetry,
+    set_trace: SetTrace,
+    work_done_progress_create: WorkDoneProgressCreate<T>,
+    work_done_progress_cancel: WorkDoneProgressCancel,
+    show_document: ShowDocument<T>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct WindowClientCapabilities {
+    pub(crate) work_done_progress: bool,
+    pub(crate) show_message: ShowMessageRequestClientCapabilities,
+    pub(crate) show_document: ShowDocumentClientCapabilities
 }
 
 /// The priority of a message which is either logged or shown to the user.
-/// On debug builds, all messages are shown.
-/// On release builds, only messages with a priority of `Info` or higher are shown.
+/// By default all log records are forwarded on debug builds and only records
+/// with a priority of `Info` or higher on release builds, which can be changed via [`Server::set_log_level`].
 #[repr(i32)]
 #[derive(Serialize_repr, Debug, Clone, Copy)]
 pub enum MessageType {
@@ -42,6 +62,10 @@ impl<T: TypeProvider> WindowService<T> {
     pub(super) fn resolve(&self, method: &str) -> Option<Callback<Server<T>>> {
         match method {
             ShowMessageRequest::<T>::METHOD => Some(self.show_message_request.callback()),
+            SetTrace::METHOD => Some(self.set_trace.callback()),
+            WorkDoneProgressCreate::<T>::METHOD => Some(self.work_done_progress_create.callback()),
+            WorkDoneProgressCancel::METHOD => Some(self.work_done_progress_cancel.callback()),
+            ShowDocument::<T>::METHOD => Some(self.show_document.callback()),
             _ => None
         }
     }
@@ -50,10 +74,14 @@ impl<T: TypeProvider> WindowService<T> {
 impl<T: TypeProvider> Default for WindowService<T> {
     fn default() -> Self {
         Self {
-            show_message: ShowMessage::default(),
+            show_message: ShowMessage,
             show_message_request: ShowMessageRequest::default(),
-            log_message: LogMessage::default(),
-            telemetry: Telemetry::default(),
+            log_message: LogMessage,
+            telemetry: Telemetry,
+            set_trace: SetTrace,
+            work_done_progress_create: WorkDoneProgressCreate::default(),
+            work_done_progress_cancel: WorkDoneProgressCancel,
+            show_document: ShowDocument::default(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,78 @@
+//! implementation of partial results and client initiated progress via the `$/progress` notification.
+//!
+//! # Usage
+//! Clients may attach a `workDoneToken` and a `partialResultToken` to requests,
+//! which are available via [`Connection::request_context`] while the request is handled.
+//! If a work done token is present, [`Connection::start_progress`] will use it instead of creating a new progress.
+//! If a partial result token is present, [`Connection::partial_result`] may be used to stream batches of the result to the client,
+//! in which case the handler should return an empty result once it is done.
+//! ```
+//! use sync_lsp::{Transport, TypeProvider, Server};
+//!
+//! struct MyServerState;
+//!
+//! #[sync_lsp::type_provider]
+//! impl TypeProvider for MyServerState {}
+//!
+//! fn main() {
+//!     let transport = Transport::stdio();
+//!     let mut server = Server::new(MyServerState, transport);
+//!
+//!     server.on_symbol(|server, _| {
+//!         let mut result = Vec::new();
+//!         let _progress = server.connection.start_progress("Searching".to_string(), true);
+//!
+//!         for _ in 0..10 {
+//!             let batch = Vec::new();
+//!             // Search for symbols here
+//!             if !server.connection.partial_result(&batch) {
+//!                 result.extend(batch);
+//!             }
+//!         }
+//!
+//!         result
+//!     });
+//!
+//!     server.serve().unwrap();
+//! }
+//! ```
+
+use serde::{Serialize, Deserialize};
+use crate::{Connection, TypeProvider};
+use crate::connection::RpcConnection;
+
+use super::work_done_progress::{ProgressToken, ProgressParams};
+
+/// Additional information, which the client attached to the request that is currently being handled.
+#[derive(Deserialize, Default, Debug, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RequestContext {
+    /// A token, which should be used to report work done progress.
+    pub work_done_token: Option<ProgressToken>,
+    /// A token, which should be used to report partial results.
+    pub partial_result_token: Option<ProgressToken>
+}
+
+impl<T: TypeProvider> Connection<T> {
+
+    /// Returns the [context](self) of the request, which is currently being handled.
+    /// Outside of request handlers, all tokens will be `None`.
+    pub fn request_context(&self) -> &RequestContext {
+        &self.context
+    }
+
+    /// Sends a batch of [partial results](self) for the current request to the client.
+    ///
+    /// # Argument
+    /// * `batch` - A part of the result, which has the same type as the final result.
+    /// * `return` - A boolean indicating whether the batch was sent, which is only the case if the client provided a partial result token.
+    pub fn partial_result(&mut self, batch: impl Serialize) -> bool {
+        let Some(token) = self.context.partial_result_token.clone() else { return false };
+        self.flush_notifications();
+        self.notify("$/progress", ProgressParams {
+            token: &token,
+            value: batch
+        });
+        true
+    }
+}
@@ -0,0 +1,98 @@
+//! implements the `window/showDocument` request.
+//!
+//! # Usage
+//! This request may be used to ask the client to display a particular resource,
+//! either inside the editor or in an external program like a web browser.
+//! [`Connection::show_document`] will only send the request if the client supports it
+//! and the result can be retrieved using [`Server::on_show_document_response`].
+
+use serde::{Serialize, Deserialize};
+use crate::{Connection, Server, TypeProvider};
+use crate::connection::{RpcConnection, Callback};
+use crate::text_document::{DocumentUri, Range};
+
+pub(super) struct ShowDocument<T: TypeProvider> {
+    callback: Callback<Server<T>>
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct ShowDocumentClientCapabilities {
+    pub(crate) support: bool
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShowDocumentParams {
+    uri: DocumentUri,
+    external: bool,
+    take_focus: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selection: Option<Range>
+}
+
+/// This response is sent when the client has processed a [`Connection::show_document`] request.
+#[derive(Deserialize, Debug, Default)]
+pub struct ShowDocumentResult {
+    /// Indicates whether the document was shown successfully.
+    pub success: bool
+}
+
+impl<T: TypeProvider> Connection<T> {
+
+    /// This request asks the client to [show a document](self).
+    ///
+    /// # Arguments
+    /// * `tag` - A tag of type [`TypeProvider::ShowDocumentData`] preserved throughout the request.
+    /// * `uri` - The uri of the document to show.
+    /// * `external` - Whether the document should be shown in an external program.
+    /// * `take_focus` - Whether the editor showing the document should take focus.
+    /// * `selection` - An optional range, which will be selected if the document is a text document.
+    /// * `result` - A boolean indicating whether the request was sent, which is not the case if the client doesn't support it.
+    pub fn show_document(&mut self, tag: T::ShowDocumentData, uri: DocumentUri, external: bool, take_focus: bool, selection: Option<Range>) -> bool {
+        if !self.capabilities.window.show_document.support {
+            return false
+        }
+
+        self.request(
+            ShowDocument::<T>::METHOD,
+            tag,
+            ShowDocumentParams {
+                uri,
+                external,
+                take_focus,
+                selection
+            }
+        )
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Set the response handler for [showing a document](self)
+    ///
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as a response from [`Connection::show_document`] is received:
+    ///     * The server instance receiving the response.
+    ///     * A tag of type [`TypeProvider::ShowDocumentData`] that was passed to the request.
+    ///     * The response data of the client, which indicates failure if the request failed.
+    pub fn on_show_document_response(&mut self, callback: fn(&mut Server<T>, T::ShowDocumentData, ShowDocumentResult)) {
+        self.window.show_document.callback = Callback::response(callback);
+    }
+}
+
+impl<T: TypeProvider> Default for ShowDocument<T> {
+    fn default() -> Self {
+        Self {
+            callback: Callback::response(|_, _: T::ShowDocumentData, _: ShowDocumentResult| ())
+        }
+    }
+}
+
+impl<T: TypeProvider> ShowDocument<T> {
+    pub(super) const METHOD: &'static str = "window/showDocument";
+
+    pub(super) fn callback(&self) -> Callback<Server<T>> {
+        self.callback.clone()
+    }
+}
@@ -28,7 +28,6 @@ impl<T: TypeProvider> Connection<T> {
     /// # Arguments
     /// * `r#type` - The type of message to show.
     /// * `message` - The message to show.
-    
     pub fn show_message(&mut self, r#type: MessageType, message: String) {
         self.notify(
             ShowMessage::METHOD, 
@@ -4,11 +4,15 @@
 //! This request may be used to query the user for a choice.
 //! The result can be retrieved using [`Server::on_show_message_response`] and therefore
 //! [`Connection::show_message_request`] won't block the current thread.
+//! The callback is also called if the user dismissed the query or the request failed.
+//! If the client supports additional properties on message actions, the selected action is identified
+//! by its index, so that multiple actions may share the same title. Otherwise actions are identified by their title,
+//! which is why requests with duplicate titles aren't sent to such clients.
 
-use std::collections::HashMap;
-use std::mem::replace;
+use std::collections::HashSet;
+use log::error;
 use serde::{Serialize, Deserialize};
-use crate::{Connection, Server, TypeProvider};
+use crate::{Connection, Server, TypeProvider, ResponseError};
 use crate::connection::{RpcConnection, Callback};
 
 use super::MessageType;
@@ -17,6 +21,18 @@ pub(super) struct ShowMessageRequest<T: TypeProvider> {
     callback: Callback<Server<T>>
 }
 
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct ShowMessageRequestClientCapabilities {
+    message_action_item: MessageActionItemClientCapabilities
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct MessageActionItemClientCapabilities {
+    additional_properties_support: bool
+}
+
 /// This Item is beeing sent along every show message request.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(bound = "")]
@@ -30,14 +46,32 @@ pub struct MessageActionItem<T: Default> {
     pub data: T
 }
 
+/// The outcome of a [show message request](self), which is passed to [`Server::on_show_message_response`].
+#[derive(Debug)]
+pub enum ShowMessageResponse<T: Default> {
+    /// The user selected one of the actions.
+    Selected(MessageActionItem<T>),
+    /// The user dismissed the query without selecting an action.
+    Dismissed,
+    /// The client returned an error.
+    Failed(ResponseError)
+}
+
 #[derive(Serialize)]
-#[serde(bound = "")]
-struct ShowMessageRequestParams<T: Default> {
+struct ShowMessageRequestParams {
     #[serde(rename = "type")]
     r#type: MessageType,
     message: String,
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    actions: Vec<MessageActionItem<T>>
+    actions: Vec<MessageActionItemParams>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageActionItemParams {
+    title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sync_lsp_index: Option<usize>
 }
 
 impl<T: TypeProvider> Connection<T> {
@@ -47,24 +81,31 @@ impl<T: TypeProvider> Connection<T> {
     /// # Arguments
     /// * `r#type` - The type of message to show.
     /// * `message` - The message to show.
-    /// * `actions` - The actions to show.
+    /// * `actions` - The actions to show. Their titles have to be unique, unless the client
+    ///   supports additional properties on message actions.
     /// * `result` - A boolean indicating whether the request was sent.
-    
-    pub fn show_message_request(&mut self, r#type: MessageType, message: String, mut actions: Vec<MessageActionItem<T::ShowMessageRequestData>>) -> bool {
+    pub fn show_message_request(&mut self, r#type: MessageType, message: String, actions: Vec<MessageActionItem<T::ShowMessageRequestData>>) -> bool {
+        let indexed = self.capabilities.window.show_message.message_action_item.additional_properties_support;
+        let mut titles = HashSet::new();
+        if !indexed && !actions.iter().all(|action| titles.insert(action.title.as_str())) {
+            error!("Failed to show message request: the client can't distinguish actions with the same title");
+            return false
+        }
+
+        let (titles, tag): (Vec<_>, Vec<_>) = actions.into_iter()
+            .map(|action| (action.title.clone(), (action.title, action.data)))
+            .unzip();
+
         self.request(
             ShowMessageRequest::<T>::METHOD,
-            {
-                let mut hashmap = HashMap::new();
-                for action in actions.iter_mut() {
-                    let data = replace(&mut action.data, Default::default());
-                    hashmap.insert(action.title.clone(), data);
-                }
-                hashmap
-            },
+            tag,
             ShowMessageRequestParams {
                 r#type,
                 message,
-                actions
+                actions: titles.into_iter().enumerate().map(|(index, title)| MessageActionItemParams {
+                    title,
+                    sync_lsp_index: indexed.then_some(index)
+                }).collect()
             }
         )
     }
@@ -75,16 +116,31 @@ impl<T: TypeProvider> Server<T> {
     /// Set the response handler for [showing a message request](self)
     ///
     /// # Argument
-    /// * `callback` - A callback which is called with the following parameters if the result of a query is received:
+    /// * `callback` - A callback which is called with the following parameters as soon as a response from [`Connection::show_message_request`] is received:
     ///     * The server instance receiving the response.
-    ///     * A tag of type [`TypeProvider::ShowMessageRequestData`] that was passed to the request.
-    
-    pub fn on_show_message_response(&mut self, callback: fn(&mut Server<T>, MessageActionItem<T::ShowMessageRequestData>)) {
-        self.window.show_message_request.callback = Callback::response(move |connection, mut tag: HashMap<String, T::ShowMessageRequestData>, params: Option<MessageActionItem<T::ShowMessageRequestData>>| {
-            if let Some(mut action) = params {
-                action.data = tag.remove(action.title.as_str()).unwrap_or_default();
-                callback(connection, action)
-            }
+    ///     * The [`ShowMessageResponse`], which contains the selected action along with its [`TypeProvider::ShowMessageRequestData`] if one was selected.
+    pub fn on_show_message_response(&mut self, callback: fn(&mut Server<T>, ShowMessageResponse<T::ShowMessageRequestData>)) {
+        self.window.show_message_request.callback = Callback::response_result(move |server, tag: Vec<(String, T::ShowMessageRequestData)>, result: Result<Option<MessageActionItemParams>, ResponseError>| {
+            callback(server, ShowMessageResponse::new(tag, result))
+        })
+    }
+}
+
+impl<T: Default> ShowMessageResponse<T> {
+    fn new(mut actions: Vec<(String, T)>, result: Result<Option<MessageActionItemParams>, ResponseError>) -> Self {
+        let selected = match result {
+            Ok(Some(selected)) => selected,
+            Ok(None) => return ShowMessageResponse::Dismissed,
+            Err(error) => return ShowMessageResponse::Failed(error)
+        };
+
+        let index = selected.sync_lsp_index
+            .filter(|index| actions.get(*index).is_some_and(|(title, _)| *title == selected.title))
+            .or_else(|| actions.iter().position(|(title, _)| *title == selected.title));
+
+        ShowMessageResponse::Selected(MessageActionItem {
+            data: index.map(|index| actions.swap_remove(index).1).unwrap_or_default(),
+            title: selected.title
         })
     }
 }
@@ -92,7 +148,7 @@ impl<T: TypeProvider> Server<T> {
 impl<T: TypeProvider> Default for ShowMessageRequest<T> {
     fn default() -> Self {
         Self {
-            callback: Callback::response(|_, _: HashMap<String, T::ShowMessageRequestData>, _: Option<MessageActionItem<T::ShowMessageRequestData>>| {})
+            callback: Callback::response(|_, _: Vec<(String, T::ShowMessageRequestData)>, _: Option<MessageActionItemParams>| {})
         }
     }
 }
@@ -103,4 +159,4 @@ impl<T: TypeProvider> ShowMessageRequest<T> {
     pub(crate) fn callback(&self) -> Callback<Server<T>> {
         self.callback.clone()
     }
-}
\ No newline at end of file
+}
@@ -1,30 +1,261 @@
-//! implementation of the `window/telemetry` notification.
-//! 
+//! implementation of the `telemetry/event` notification.
+//!
 //! # Usage
-//! [`Connection::telemetry`] sends arbitrary telemetry data to the client.
+//! [`Connection::telemetry`] sends arbitrary telemetry data to the client, while [`Connection::telemetry_event`]
+//! sends typed events of [`TypeProvider::TelemetryEvent`], which can be defined using the [`TelemetryEvent`] derive macro.
+//! Each typed event is sent as an object with a `name` and the fields of the event as `properties`.
+//! Additionally [`Server::set_automatic_telemetry`] enables built-in events, which report the latency of
+//! each handled request or notification (`sync-lsp/latency`), handler errors (`sync-lsp/error`) and panics (`sync-lsp/panic`).
+//!
+//! Clients may opt out of all telemetry or reduce the share of typed and latency events sent, by passing
+//! the following initialization options: `{ "telemetry": { "enabled": true, "sampleRate": 0.1 } }`.
+//! Errors and panics are never sampled.
+//!
+//! # Example
+//! ```
+//! use sync_lsp::{Transport, TypeProvider, Server};
+//! use sync_lsp::window::telemetry::TelemetryEvent;
+//!
+//! #[derive(TelemetryEvent)]
+//! enum MyTelemetryEvent {
+//!     #[telemetry(name = "indexed")]
+//!     Indexed { files: usize, milliseconds: u64 },
+//!     Restarted
+//! }
+//!
+//! struct MyServerState;
+//!
+//! #[sync_lsp::type_provider]
+//! impl TypeProvider for MyServerState {
+//!     type TelemetryEvent = MyTelemetryEvent;
+//! }
+//!
+//! fn main() {
+//!     let transport = Transport::stdio();
+//!     let mut server = Server::new(MyServerState, transport);
+//!     server.set_automatic_telemetry(true);
+//!
+//!     server.on_open(|server, _| {
+//!         server.connection.telemetry_event(MyTelemetryEvent::Indexed {
+//!             files: 1,
+//!             milliseconds: 0
+//!         });
+//!     });
+//!
+//!     server.serve().unwrap();
+//! }
+//! ```
 
-use serde::Serialize;
-use crate::{Connection, TypeProvider};
-use crate::connection::RpcConnection;
+use std::time::Duration;
+use serde::{Serialize, Serializer, Deserialize};
+use serde_json::Value;
+use crate::{Connection, Server, TypeProvider, ErrorCode};
+use crate::connection::{RpcConnection, Outcome};
+pub use sync_lsp_derive::TelemetryEvent;
 
 #[derive(Default)]
 pub(super) struct Telemetry;
 
+/// Defines typed telemetry events, which can be sent via [`Connection::telemetry_event`].
+/// Instead of implementing this trait manually, you can use the `TelemetryEvent` derive macro,
+/// which supports structs and enums with named fields. The name of an event defaults to the name of
+/// the struct or variant and can be changed with the `#[telemetry(name = "...")]` attribute.
+pub trait TelemetryEvent {
+    /// Returns the name and the field names of every event, which describes the schema of this type.
+    fn schema() -> Vec<(&'static str, Vec<&'static str>)>;
+    /// Returns the name of this event.
+    fn name(&self) -> &'static str;
+    /// Serializes the fields of this event as a map.
+    fn serialize_fields<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+/// A telemetry event without any variants, which is used if no custom events are needed.
+#[derive(Debug, Clone)]
+pub enum UnitTelemetryEvent {}
+
+/// The client side telemetry settings and whether built-in events are enabled.
+pub(crate) struct TelemetryState {
+    enabled: bool,
+    sample_rate: f64,
+    sampled: f64,
+    automatic: bool
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TelemetryInitializationOptions {
+    telemetry: TelemetryOptions
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct TelemetryOptions {
+    enabled: bool,
+    sample_rate: f64
+}
+
+#[derive(Serialize)]
+struct TelemetryPayload<'a, P: Serialize> {
+    name: &'a str,
+    properties: P
+}
+
+struct Fields<'a, E: TelemetryEvent>(&'a E);
+
+#[derive(Serialize)]
+struct LatencyProperties<'a> {
+    method: &'a str,
+    milliseconds: f64
+}
+
+#[derive(Serialize)]
+struct ErrorProperties<'a> {
+    method: &'a str,
+    code: ErrorCode,
+    message: &'a str
+}
+
+#[derive(Serialize)]
+struct PanicProperties<'a> {
+    method: &'a str,
+    message: &'a str
+}
+
 impl<T: TypeProvider> Connection<T> {
 
-    /// This notification sends arbitrary [telemetry data](self) to the client.
-    /// 
+    /// This notification sends arbitrary [telemetry data](self) to the client,
+    /// unless the client opted out of telemetry.
+    ///
     /// # Arguments
     /// * `params` - The data to send.
-
     pub fn telemetry(&mut self, params: impl Serialize) {
+        if !self.telemetry.enabled { return }
         self.notify(
             Telemetry::METHOD,
             params
         );
     }
+
+    /// Sends a typed [telemetry event](self) to the client, if it isn't skipped due to
+    /// sampling or the client opted out of telemetry.
+    ///
+    /// # Arguments
+    /// * `event` - The event to send.
+    pub fn telemetry_event(&mut self, event: T::TelemetryEvent) {
+        if !self.telemetry.sample() { return }
+        self.telemetry(TelemetryPayload {
+            name: event.name(),
+            properties: Fields(&event)
+        });
+    }
+
+    /// Sends the built-in events for a handled request or notification.
+    pub(crate) fn record_telemetry(&mut self, method: &str, elapsed: Duration, outcome: &Outcome) {
+        if !self.telemetry.automatic { return }
+
+        if !matches!(outcome, Outcome::Panic(..)) && self.telemetry.sample() {
+            self.telemetry(TelemetryPayload {
+                name: "sync-lsp/latency",
+                properties: LatencyProperties {
+                    method,
+                    milliseconds: elapsed.as_secs_f64() * 1000.0
+                }
+            });
+        }
+
+        match *outcome {
+            Outcome::Success | Outcome::Cancelled => (),
+            Outcome::Error(code, message) => self.telemetry(TelemetryPayload {
+                name: "sync-lsp/error",
+                properties: ErrorProperties {
+                    method,
+                    code,
+                    message
+                }
+            }),
+            Outcome::Panic(message) => self.telemetry(TelemetryPayload {
+                name: "sync-lsp/panic",
+                properties: PanicProperties {
+                    method,
+                    message
+                }
+            })
+        }
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets whether built-in [telemetry events](self) should be sent for each handled request and notification.
+    /// This is disabled by default.
+    ///
+    /// # Argument
+    /// * `value` - If `true`, latency, error and panic events will be sent, unless the client opted out.
+    pub fn set_automatic_telemetry(&mut self, value: bool) {
+        self.connection.telemetry.automatic = value;
+    }
+}
+
+impl TelemetryState {
+    /// Reads the telemetry settings from the initialization options sent by the client.
+    pub(crate) fn configure(&mut self, options: &Value) {
+        if let Ok(options) = TelemetryInitializationOptions::deserialize(options) {
+            self.enabled = options.telemetry.enabled;
+            self.sample_rate = options.telemetry.sample_rate.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Returns whether the next sampled event should be sent, which
+    /// is the case for exactly the configured share of events.
+    fn sample(&mut self) -> bool {
+        if !self.enabled { return false }
+        self.sampled += self.sample_rate;
+        if self.sampled < 1.0 { return false }
+        self.sampled -= 1.0;
+        true
+    }
+}
+
+impl Default for TelemetryState {
+    fn default() -> Self {
+        TelemetryState {
+            enabled: true,
+            sample_rate: 1.0,
+            sampled: 0.0,
+            automatic: false
+        }
+    }
+}
+
+impl Default for TelemetryOptions {
+    fn default() -> Self {
+        TelemetryOptions {
+            enabled: true,
+            sample_rate: 1.0
+        }
+    }
+}
+
+impl<E: TelemetryEvent> Serialize for Fields<'_, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_fields(serializer)
+    }
+}
+
+impl TelemetryEvent for UnitTelemetryEvent {
+    fn schema() -> Vec<(&'static str, Vec<&'static str>)> {
+        Vec::new()
+    }
+
+    fn name(&self) -> &'static str {
+        match *self {}
+    }
+
+    fn serialize_fields<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        match *self {}
+    }
 }
 
 impl Telemetry {
     const METHOD: &'static str = "telemetry/event";
-}
\ No newline at end of file
+}
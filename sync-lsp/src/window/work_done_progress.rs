@@ -0,0 +1,330 @@
+//! implementation of work done progress via the `window/workDoneProgress/create` request
+//! and the `$/progress` notification.
+//!
+//! # Usage
+//! [`Connection::start_progress`] creates a new progress on the client side and returns a [`WorkDoneProgress`],
+//! which may be used to report updates and ends the progress once it is dropped.
+//! Until the client has confirmed the creation of the progress, only the latest update is kept and sent afterwards.
+//! If the client fails to create the progress or the handle is dropped before, nothing is sent at all.
+//! The handle can be sent to other threads, in which case all updates are sent by the thread running the server.
+//! While a handler runs on that thread, the confirmation is only handled within [`Connection::cancelled`],
+//! so long running handlers should either poll it regularly or move the work to another thread, like in the example below.
+//! If the progress is cancellable, the client may cancel it via `window/workDoneProgress/cancel`,
+//! which can be checked by calling [`WorkDoneProgress::cancelled`]. A progress started by a request handler
+//! is tied to that request, so that cancelling the progress also cancels the request as far as [`Connection::cancelled`] is concerned.
+//! ```
+//! use std::thread;
+//! use sync_lsp::{Transport, TypeProvider, Server};
+//!
+//! struct MyServerState;
+//!
+//! #[sync_lsp::type_provider]
+//! impl TypeProvider for MyServerState {}
+//!
+//! fn main() {
+//!     let transport = Transport::stdio();
+//!     let mut server = Server::new(MyServerState, transport);
+//!
+//!     server.on_open(|server, _| {
+//!         let progress = server.connection.start_progress("Indexing".to_string(), true);
+//!         thread::spawn(move || {
+//!             for file in 0..100 {
+//!                 if progress.cancelled() { break }
+//!                 progress.report(Some(format!("File {file}")), Some(file));
+//!             }
+//!         });
+//!     });
+//!
+//!     server.serve().unwrap();
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::mem::replace;
+use std::sync::{Arc, Mutex, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use log::error;
+use serde::{Serialize, Deserialize};
+use serde_json::from_str;
+use crate::{Connection, Server, TypeProvider, ResponseError};
+use crate::connection::{Callback, RpcConnection, Notifier, MessageID};
+
+/// A token, which identifies a progress and is either chosen by the server or by the client.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum ProgressToken {
+    Number(i32),
+    String(String)
+}
+
+/// A handle to a progress, which is shown by the client until this handle is dropped.
+/// If the client doesn't support work done progress, this handle won't send anything.
+pub struct WorkDoneProgress {
+    token: ProgressToken,
+    sink: Arc<Mutex<ProgressSink>>,
+    cancelled: Arc<AtomicBool>
+}
+
+/// The destination of updates of a progress, which depends on whether the client has created it yet.
+enum ProgressSink {
+    /// The creation hasn't been confirmed yet, so the begin and the latest report are held back.
+    Pending {
+        begin: WorkDoneProgressValue,
+        report: Option<WorkDoneProgressValue>
+    },
+    Created(Notifier),
+    Disabled
+}
+
+pub(super) struct WorkDoneProgressCreate<T: TypeProvider> {
+    callback: Callback<Server<T>>
+}
+
+#[derive(Default)]
+pub(crate) struct WorkDoneProgressCancel;
+
+/// All progresses started by the server, which haven't been dropped yet.
+#[derive(Default)]
+pub(crate) struct ProgressState {
+    next: i32,
+    active: HashMap<ProgressToken, ActiveProgress>,
+    pending: HashMap<ProgressToken, Weak<Mutex<ProgressSink>>>
+}
+
+struct ActiveProgress {
+    cancelled: Weak<AtomicBool>,
+    request: Option<MessageID>
+}
+
+#[derive(Serialize)]
+struct WorkDoneProgressCreateParams<'a> {
+    token: &'a ProgressToken
+}
+
+#[derive(Deserialize)]
+pub(crate) struct WorkDoneProgressCancelParams {
+    token: ProgressToken
+}
+
+#[derive(Serialize)]
+pub(crate) struct ProgressParams<'a, V: Serialize> {
+    pub(crate) token: &'a ProgressToken,
+    pub(crate) value: V
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(crate) enum WorkDoneProgressValue {
+    Begin {
+        title: String,
+        cancellable: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        percentage: Option<u32>
+    },
+    Report {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        percentage: Option<u32>
+    },
+    End {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>
+    }
+}
+
+impl<T: TypeProvider> Connection<T> {
+
+    /// Creates a new [progress](self) on the client side, which starts at 0 percent.
+    /// The progress ends as soon as the returned handle is dropped. If the current request
+    /// carries a work done token, that token is used instead of creating a new one.
+    /// Otherwise, updates are held back until the client has confirmed the creation,
+    /// which is handled within [`Connection::cancelled`] or once the current handler has returned.
+    ///
+    /// # Arguments
+    /// * `title` - The title of the progress, which briefly describes the operation.
+    /// * `cancellable` - Whether the client should show a button to cancel the operation.
+    /// * `return` - A handle, which may be used to report updates.
+    pub fn start_progress(&mut self, title: String, cancellable: bool) -> WorkDoneProgress {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let begin = WorkDoneProgressValue::Begin {
+            title,
+            cancellable,
+            message: None,
+            percentage: Some(0)
+        };
+
+        let (token, sink) = match self.context.work_done_token.take() {
+            Some(token) => {
+                self.notify("$/progress", ProgressParams { token: &token, value: begin });
+                (token, ProgressSink::Created(self.notifier()))
+            },
+            None => {
+                let token = self.progress.next_token();
+                let created = self.capabilities.window.work_done_progress && self.request(
+                    WorkDoneProgressCreate::<T>::METHOD,
+                    &token,
+                    WorkDoneProgressCreateParams { token: &token }
+                );
+
+                match created {
+                    true => (token, ProgressSink::Pending { begin, report: None }),
+                    false => (token, ProgressSink::Disabled)
+                }
+            }
+        };
+
+        let pending = matches!(sink, ProgressSink::Pending { .. });
+        let sink = Arc::new(Mutex::new(sink));
+        if pending {
+            self.progress.pending.retain(|_, sink| sink.strong_count() > 0);
+            self.progress.pending.insert(token.clone(), Arc::downgrade(&sink));
+        }
+
+        let request = self.current_request.clone();
+        self.progress.insert(token.clone(), &cancelled, request);
+
+        WorkDoneProgress {
+            token,
+            sink,
+            cancelled
+        }
+    }
+
+    /// Sends the updates of a progress, which have been held back until the client answered the create request.
+    /// Progresses, whose handle has been dropped in the meantime, are skipped, since they have already ended.
+    fn created_progress(&mut self, token: ProgressToken, result: Result<(), ResponseError>) {
+        let Some(sink) = self.progress.pending.remove(&token).and_then(|sink| sink.upgrade()) else { return };
+        let Ok(mut sink) = sink.lock() else { return };
+
+        if let Err(error) = result {
+            *sink = ProgressSink::Disabled;
+            return error!("Failed to create progress: {}", error.message)
+        }
+
+        let ProgressSink::Pending { begin, report } = replace(&mut *sink, ProgressSink::Disabled) else { return };
+        for value in [Some(begin), report].into_iter().flatten() {
+            self.notify("$/progress", ProgressParams { token: &token, value });
+        }
+
+        *sink = ProgressSink::Created(self.notifier());
+    }
+
+    /// Handles the responses to create requests, which have been received while a handler is running.
+    pub(crate) fn take_created_progress(&mut self) {
+        if self.progress.pending.is_empty() { return }
+
+        for (tag, result) in self.take_responses(WorkDoneProgressCreate::<T>::METHOD) {
+            match from_str(&tag) {
+                Ok(token) => self.created_progress(token, result.map(|_| ())),
+                Err(error) => error!("Failed to parse id: {tag}: {error}")
+            }
+        }
+    }
+}
+
+impl WorkDoneProgress {
+
+    /// Reports an update of this [progress](self) to the client.
+    ///
+    /// # Arguments
+    /// * `message` - An optional message, which describes the current state of the operation.
+    /// * `percentage` - An optional percentage between 0 and 100.
+    pub fn report(&self, message: Option<String>, percentage: Option<u32>) {
+        let value = WorkDoneProgressValue::Report {
+            message,
+            percentage: percentage.map(|percentage| percentage.min(100))
+        };
+
+        match self.sink.lock().as_deref_mut() {
+            Ok(ProgressSink::Pending { report, .. }) => *report = Some(value),
+            Ok(ProgressSink::Created(notifier)) => notifier.notify("$/progress", ProgressParams { token: &self.token, value }),
+            _ => {}
+        }
+    }
+
+    /// Returns whether the client has cancelled this [progress](self).
+    pub fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the token, which identifies this [progress](self).
+    pub fn token(&self) -> &ProgressToken {
+        &self.token
+    }
+}
+
+impl Drop for WorkDoneProgress {
+    fn drop(&mut self) {
+        if let Ok(ProgressSink::Created(notifier)) = self.sink.lock().as_deref_mut() {
+            notifier.notify("$/progress", ProgressParams {
+                token: &self.token,
+                value: WorkDoneProgressValue::End {
+                    message: None
+                }
+            });
+        }
+    }
+}
+
+impl ProgressState {
+    fn next_token(&mut self) -> ProgressToken {
+        self.next += 1;
+        ProgressToken::String(format!("sync-lsp/{}", self.next))
+    }
+
+    fn insert(&mut self, token: ProgressToken, cancelled: &Arc<AtomicBool>, request: Option<MessageID>) {
+        self.active.retain(|_, progress| progress.cancelled.strong_count() > 0);
+        self.active.insert(token, ActiveProgress {
+            cancelled: Arc::downgrade(cancelled),
+            request
+        });
+    }
+
+    /// Marks a progress as cancelled and returns the request it has been started by.
+    pub(crate) fn cancel(&mut self, params: WorkDoneProgressCancelParams) -> Option<&MessageID> {
+        let progress = self.active.get(&params.token)?;
+        progress.cancelled.upgrade()?.store(true, Ordering::Relaxed);
+        progress.request.as_ref()
+    }
+
+    /// Checks whether any progress started by the given request has been cancelled.
+    pub(crate) fn cancelled(&self, request: &MessageID) -> bool {
+        self.active.values().any(|progress| {
+            progress.request.as_ref() == Some(request) && progress.cancelled.upgrade()
+                .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+        })
+    }
+}
+
+impl<T: TypeProvider> Default for WorkDoneProgressCreate<T> {
+    fn default() -> Self {
+        Self {
+            callback: Callback::response_result(|server: &mut Server<T>, token: Option<ProgressToken>, result: Result<(), ResponseError>| {
+                if let Some(token) = token {
+                    server.connection.created_progress(token, result);
+                }
+            })
+        }
+    }
+}
+
+impl<T: TypeProvider> WorkDoneProgressCreate<T> {
+    pub(super) const METHOD: &'static str = "window/workDoneProgress/create";
+
+    pub(super) fn callback(&self) -> Callback<Server<T>> {
+        self.callback.clone()
+    }
+}
+
+impl WorkDoneProgressCancel {
+    pub(crate) const METHOD: &'static str = "window/workDoneProgress/cancel";
+
+    pub(super) fn callback<T: TypeProvider>(&self) -> Callback<Server<T>> {
+        Callback::notification(|server: &mut Server<T>, params: WorkDoneProgressCancelParams| {
+            server.connection.progress.cancel(params);
+        })
+    }
+}
@@ -45,7 +45,6 @@ impl<T: TypeProvider> Connection<T> {
     /// * `tag` - A tag of type [`TypeProvider::ApplyEditData`] preserved throughout the request.
     /// * `edit` - The workspace edit to apply.
     /// * `result` - A boolean indicating whether the request was sent.
-    
     pub fn apply_edit(&mut self, tag: T::ApplyEditData, edit: WorkspaceEdit) -> bool {
         self.request(
             ApplyEdit::<T>::METHOD,
@@ -64,7 +63,6 @@ impl<T: TypeProvider> Server<T> {
     ///     * The server instance receiving the response.
     ///     * A tag of type [`TypeProvider::ApplyEditData`] that was passed to the request.
     ///     * The response data of the client.
-
     pub fn on_apply_edit_response(&mut self, callback: fn(&mut Server<T>, T::ApplyEditData, ApplyWorkspaceEditResponse)) {
         self.workspace.apply_edit.callback = Callback::response(callback);
     }
@@ -0,0 +1,105 @@
+//! implementation of the `workspace/diagnostic` request
+//!
+//! # Usage
+//! Clients supporting the pull model may request diagnostics of all documents in the workspace
+//! via [`Server::on_workspace_diagnostic`], including documents, which aren't open.
+//! The result ids of previous reports are passed to the callback, so that it can answer with
+//! [`DocumentDiagnosticReport::Unchanged`] for documents, whose diagnostics haven't changed.
+//! Since clients may request workspace diagnostics repeatedly, such reports should be preferred
+//! whenever possible. Diagnostics of single documents are provided via [`Server::on_diagnostic`].
+
+use serde::{Serialize, Deserialize};
+use log::error;
+use crate::{Server, TypeProvider};
+use crate::connection::{Callback, Endpoint};
+use crate::text_document::DocumentUri;
+use crate::text_document::diagnostic::DocumentDiagnosticReport;
+
+#[derive(Default, Clone)]
+pub(crate) struct WorkspaceDiagnosticOptions;
+
+/// The server side `workspace/diagnostic/refresh` request, which is sent via [`Server::refresh_diagnostics`].
+pub(crate) struct DiagnosticRefresh;
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct DiagnosticWorkspaceClientCapabilities {
+    pub(crate) refresh_support: bool
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceDiagnosticParams {
+    previous_result_ids: Vec<PreviousResultId>
+}
+
+/// The result id of a previous report for a document.
+#[derive(Deserialize, Debug, Clone)]
+pub struct PreviousResultId {
+    /// The uri of the document.
+    pub uri: DocumentUri,
+    /// The result id of the previous report.
+    pub value: String
+}
+
+/// A diagnostic report for a document in the workspace.
+#[derive(Serialize, Debug, Clone)]
+pub struct WorkspaceDocumentDiagnosticReport<D> {
+    /// The uri of the document.
+    pub uri: DocumentUri,
+    /// The version of the document the diagnostics have been computed for, or `None` if it isn't open.
+    pub version: Option<i32>,
+    /// The report of the document.
+    #[serde(flatten)]
+    pub report: DocumentDiagnosticReport<D>
+}
+
+#[derive(Serialize)]
+struct WorkspaceDiagnosticReport<D> {
+    items: Vec<WorkspaceDocumentDiagnosticReport<D>>
+}
+
+impl WorkspaceDiagnosticOptions {
+
+    pub(crate) const METHOD: &'static str = "workspace/diagnostic";
+
+    pub(super) fn endpoint<T: TypeProvider>() -> Endpoint<T, WorkspaceDiagnosticOptions> {
+        Endpoint::new(Callback::request(|_, _: WorkspaceDiagnosticParams| {
+            WorkspaceDiagnosticReport::<T::DiagnosticData> {
+                items: Vec::new()
+            }
+        }))
+    }
+}
+
+impl DiagnosticRefresh {
+
+    pub(crate) const METHOD: &'static str = "workspace/diagnostic/refresh";
+
+    pub(super) fn callback<T: TypeProvider>() -> Callback<Server<T>> {
+        Callback::response_result(|_, _: (), result: Result<(), _>| {
+            if let Err(error) = result {
+                error!("Failed to refresh diagnostics: {}", error.message);
+            }
+        })
+    }
+}
+
+impl<T: TypeProvider> Server<T> {
+
+    /// Sets the callback that will be called to [compute diagnostics of the workspace](self).
+    ///
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as workspace diagnostics are requested:
+    ///     * The server instance receiving the request.
+    ///     * The [`Vec<PreviousResultId>`] containing the result ids of previous reports.
+    ///     * `return` - The reports of all documents with diagnostics.
+    #[allow(clippy::type_complexity)]
+    pub fn on_workspace_diagnostic(&mut self, callback: fn(&mut Server<T>, Vec<PreviousResultId>) -> Vec<WorkspaceDocumentDiagnosticReport<T::DiagnosticData>>) {
+        self.workspace.diagnostic.set_callback(Callback::request(move |server, params: WorkspaceDiagnosticParams| {
+            WorkspaceDiagnosticReport {
+                items: callback(server, params.previous_result_ids)
+            }
+        }));
+    }
+}
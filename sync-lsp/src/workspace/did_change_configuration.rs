@@ -39,7 +39,6 @@ impl<T: TypeProvider> Server<T> {
     /// * `callback` - A callback which is called with the following parameters if a change in configuration is received:
     ///     * The server instance receiving the response.
     ///     * The updated configuration of type [`TypeProvider::Configuration`].
-
     pub fn on_change_configuration(&mut self, callback: fn(&mut Server<T>, T::Configuration)) {
         self.workspace.did_change_configuration.set_callback(Callback::notification(move |server, params: DidChangeConfigurationParams<T::Configuration>| {
             callback(server, params.settings)
@@ -9,6 +9,7 @@ use serde_repr::Deserialize_repr;
 use serde::Deserialize;
 use crate::{Server, TypeProvider};
 use crate::connection::{Endpoint, Callback};
+use crate::text_document::DocumentUri;
 
 #[derive(Default, Clone)]
 pub(crate) struct DidChangeWatchedFilesOptions;
@@ -17,7 +18,7 @@ pub(crate) struct DidChangeWatchedFilesOptions;
 #[derive(Deserialize, Debug)]
 pub struct FileEvent {
     /// A file URI.
-    pub uri: String,
+    pub uri: DocumentUri,
     /// The file change type as defined in [`FileChangeType`].
     #[serde(rename = "type")]
     pub r#type: FileChangeType
@@ -48,7 +49,6 @@ impl<T: TypeProvider> Server<T> {
     /// * `callback` - A callback which is called with the following parameters as soon as watch file changes are received:
     ///     * The server instance receiving the response.
     ///     * A vector of [`FileEvent`]s.
-
     pub fn on_change_watched_files(&mut self, callback: fn (&mut Server<T>, Vec<FileEvent>)) {
         self.workspace.did_change_watched_files.set_callback(Callback::notification(move |server, params: DidChangeWatchedFilesParams| {
             callback(server, params.changes)
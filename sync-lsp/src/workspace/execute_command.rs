@@ -9,7 +9,7 @@
 //! ```
 //!use sync_lsp::text_document::{Range, Position};
 //!use sync_lsp::text_document::code_lens::CodeLens;
-//!use sync_lsp::{Transport, TypeProvider, Server, UnitType};
+//!use sync_lsp::{Transport, TypeProvider, Server};
 //!use sync_lsp::workspace::execute_command::Command;
 //!use log::info;
 //!
@@ -48,8 +48,8 @@
 //!                },
 //!                // This command will be executed when the user clicks on the code lens.
 //!                command: Some(MyCommand::MyCommandWithArguments(1)),
-//!                // Since we didn't override TypeProvider::CodeLensData, we have to use UnitType here.
-//!                data: UnitType
+//!                // Since we didn't override TypeProvider::CodeLensData, we have to use Option<()> here.
+//!                data: None
 //!            }
 //!        ]
 //!    });
@@ -114,7 +114,6 @@ impl<T: TypeProvider> Server<T> {
     /// * `callback` - A callback which is called with the following parameters as soon as the corresponding request is received:
     ///     * The server instance receiving the response.
     ///     * The `Command` to be executed.
-    
     pub fn on_execute_command<R: 'static + Serialize>(&mut self, callback: fn(&mut Server<T>, T::Command) -> R) {
         self.workspace.execute_command.set_callback(Callback::request(move |server, params: CommandContainer<T::Command>| {
             callback(server, params.0)
@@ -4,13 +4,21 @@
 //! The language server protocol usually operates on a workspace level.
 
 use crate::{connection::{Callback, Endpoint}, Server, TypeProvider};
-use self::{did_change_configuration::DidChangeConfigurationOptions, did_change_watched_files::DidChangeWatchedFilesOptions, symbol::SymbolOptions, execute_command::ExecuteCommandOptions, apply_edit::ApplyEdit};
+use self::{did_change_configuration::DidChangeConfigurationOptions, did_change_watched_files::DidChangeWatchedFilesOptions, symbol::SymbolOptions, execute_command::ExecuteCommandOptions, apply_edit::ApplyEdit, diagnostic::{WorkspaceDiagnosticOptions, DiagnosticRefresh, DiagnosticWorkspaceClientCapabilities}};
+use serde::Deserialize;
 
 pub mod did_change_configuration;
 pub mod did_change_watched_files;
 pub mod symbol;
 pub mod execute_command;
 pub mod apply_edit;
+pub mod diagnostic;
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct WorkspaceClientCapabilities {
+    pub(crate) diagnostics: DiagnosticWorkspaceClientCapabilities
+}
 
 /// This struct contains all workspace endpoints, which are mainly used to resolve callbacks.
 pub(crate) struct WorkspaceService<T: TypeProvider> {
@@ -18,6 +26,7 @@ pub(crate) struct WorkspaceService<T: TypeProvider> {
     pub(super) did_change_watched_files: Endpoint<T, DidChangeWatchedFilesOptions>,
     pub(super) symbol: Endpoint<T, SymbolOptions>,
     pub(super) execute_command: Endpoint<T, ExecuteCommandOptions>,
+    pub(super) diagnostic: Endpoint<T, WorkspaceDiagnosticOptions>,
     /// Apply edit is a server side request, which is why it isn't an endpoint.
     apply_edit: ApplyEdit<T>
 }
@@ -30,6 +39,8 @@ impl<T: TypeProvider> WorkspaceService<T> {
             ExecuteCommandOptions::METHOD => Some(self.execute_command.callback()),
             ApplyEdit::<T>::METHOD => Some(self.apply_edit.callback()),
             DidChangeWatchedFilesOptions::METHOD => Some(self.did_change_watched_files.callback()),
+            WorkspaceDiagnosticOptions::METHOD => Some(self.diagnostic.callback()),
+            DiagnosticRefresh::METHOD => Some(DiagnosticRefresh::callback()),
             _ => None
         }
     }
@@ -42,6 +53,7 @@ impl<T: TypeProvider> Default for WorkspaceService<T> {
             did_change_watched_files: DidChangeWatchedFilesOptions::endpoint(),
             symbol: SymbolOptions::endpoint(),
             execute_command: ExecuteCommandOptions::endpoint(),
+            diagnostic: WorkspaceDiagnosticOptions::endpoint(),
             apply_edit: ApplyEdit::default(),           
         }
     }
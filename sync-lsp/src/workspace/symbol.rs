@@ -5,6 +5,7 @@
 //! programming language. These symbols are commonly used resolve their specific
 //! location in a source file. They can either be queried for a specific file or,
 //! like in this case, for the whole workspace using [`Server::on_symbol`].
+//! Large results may be streamed to the client via [partial results](crate::window::partial_result).
 
 use crate::TypeProvider;
 use crate::text_document::Location;
@@ -80,10 +81,25 @@ impl<T: TypeProvider> Server<T> {
     /// * `callback` - A callback which is called with the following parameters as soon as the corresponding request is received:
     ///     * The server instance receiving the response.
     ///     * A possibly empty query string that is used to filter the symbols.
-    
     pub fn on_symbol(&mut self, callback: fn(&mut Server<T>, String) -> Vec<SymbolInformation>) {
         self.workspace.symbol.set_callback(Callback::request(move |server, params: WorkspaceSymbolParams| {
             callback(server, params.query)
         }))
     }
+}
+
+impl<T: TypeProvider + Clone + Send> Server<T> {
+
+    /// Sets a callback to [resolve symbols](self) in a workspace, which is run on a worker thread
+    /// if enabled via [`Server::set_worker_threads`].
+    /// 
+    /// # Argument
+    /// * `callback` - A callback which is called with the following parameters as soon as the corresponding request is received:
+    ///     * A snapshot of the server state.
+    ///     * A possibly empty query string that is used to filter the symbols.
+    pub fn on_symbol_concurrent(&mut self, callback: fn(&T, String) -> Vec<SymbolInformation>) {
+        self.workspace.symbol.set_callback(Server::concurrent(move |state, params: WorkspaceSymbolParams| {
+            callback(state, params.query)
+        }))
+    }
 }
\ No newline at end of file